@@ -0,0 +1,38 @@
+use std::fmt;
+
+/// A client that sends an argument to a server and blocks until it returns.
+///
+/// This lets code that only makes calls, such as the clients generated by
+/// `#[rpcore::service]`, work over any transport.
+pub trait Call<Arg> {
+    type Ret;
+    type Err;
+
+    fn call(&self, arg: Arg) -> Result<Self::Ret, Self::Err>;
+}
+
+/// The error of a call whose server returned the response of another method,
+/// e.g. a mismatched or misbehaving peer of a generated client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnexpectedResponse {
+    method: &'static str,
+}
+
+impl UnexpectedResponse {
+    pub const fn new(method: &'static str) -> Self {
+        Self { method }
+    }
+
+    /// Returns the name of the method that was called.
+    pub fn method(&self) -> &'static str {
+        self.method
+    }
+}
+
+impl fmt::Display for UnexpectedResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unexpected response to {}", self.method)
+    }
+}
+
+impl std::error::Error for UnexpectedResponse {}
@@ -1,3 +1,6 @@
 pub mod completion;
 pub mod readiness;
 pub mod recv;
+
+mod tokenized;
+pub use tokenized::Tokenized;
@@ -1,7 +1,47 @@
+use std::time::Duration;
+
 pub trait EventSource<Reactor> {
     type Token;
     type Err;
 
     fn register(&mut self, registry: &mut Reactor, token: Self::Token) -> Result<(), Self::Err>;
     fn deregister(&mut self, registry: &mut Reactor) -> Result<(), Self::Err>;
+
+    /// Called after the source has been reported ready and before it is
+    /// drained, e.g. to reset an eventfd counter.
+    fn on_ready(&mut self) -> Result<(), Self::Err> {
+        Ok(())
+    }
+}
+
+/// An event demultiplexer that waits for registered [`EventSource`]s to
+/// become ready.
+///
+/// Sources are identified by a `usize` key, which the reactor converts into
+/// its own token type with [`Reactor::token`].
+pub trait Reactor {
+    type Registry;
+    type Token;
+    type Err: std::error::Error;
+
+    fn registry(&mut self) -> &mut Self::Registry;
+
+    fn token(key: usize) -> Self::Token;
+
+    /// Blocks until at least one source is ready or `timeout` elapses, and
+    /// appends the keys of the ready sources to `ready`.
+    fn wait(&mut self, ready: &mut Vec<usize>, timeout: Option<Duration>) -> Result<(), Self::Err>;
+}
+
+/// An event source that produces new event sources when ready, e.g. a
+/// listener that accepts connections.
+pub trait Accept {
+    type Source;
+    type AcceptErr;
+
+    /// Accepts a pending source, or returns `Ok(None)` if there is none.
+    ///
+    /// An error fails one pending source only: the server keeps accepting
+    /// until `Ok(None)`.
+    fn try_accept(&mut self) -> Result<Option<Self::Source>, Self::AcceptErr>;
 }
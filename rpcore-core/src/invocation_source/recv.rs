@@ -15,4 +15,10 @@ pub trait TryRecvInvocation<Arg, Cb>: RecvInvocation<Arg, Cb> {
 pub trait Error: std::error::Error {
     fn is_closed(&self) -> bool;
     fn is_empty(&self) -> bool;
+
+    /// Whether the receive was interrupted to let the server check for
+    /// shutdown, rather than failed.
+    fn is_interrupted(&self) -> bool {
+        false
+    }
 }
@@ -0,0 +1,78 @@
+use crate::invocation_source::readiness::EventSource;
+use crate::invocation_source::recv::{RecvInvocation, TryRecvInvocation};
+use crate::server::{GetToken, SetToken, Token};
+use crate::Invocation;
+
+/// An invocation source that sets its token on every received argument.
+///
+/// It is used to tell apart the clients behind invocation sources that are
+/// multiplexed by one server, e.g. the connections accepted from a listener.
+#[derive(Debug)]
+pub struct Tokenized<I> {
+    pub token: Token,
+    pub inner: I,
+}
+
+impl<I> Tokenized<I> {
+    pub const fn new(token: Token, inner: I) -> Self {
+        Self { token, inner }
+    }
+
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<I> GetToken for Tokenized<I> {
+    fn token(&self) -> Token {
+        self.token
+    }
+}
+
+impl<I, Arg, Cb> RecvInvocation<Arg, Cb> for Tokenized<I>
+where
+    I: RecvInvocation<Arg, Cb>,
+    Arg: SetToken,
+{
+    type RecvErr = I::RecvErr;
+
+    fn recv(&mut self) -> Result<Invocation<Arg, Cb>, Self::RecvErr> {
+        let mut inv = self.inner.recv()?;
+        inv.arg.set_token(self.token);
+        Ok(inv)
+    }
+}
+
+impl<I, Arg, Cb> TryRecvInvocation<Arg, Cb> for Tokenized<I>
+where
+    I: TryRecvInvocation<Arg, Cb>,
+    Arg: SetToken,
+{
+    type TryRecvErr = I::TryRecvErr;
+
+    fn try_recv(&mut self) -> Result<Invocation<Arg, Cb>, Self::TryRecvErr> {
+        let mut inv = self.inner.try_recv()?;
+        inv.arg.set_token(self.token);
+        Ok(inv)
+    }
+}
+
+impl<I, Reactor> EventSource<Reactor> for Tokenized<I>
+where
+    I: EventSource<Reactor>,
+{
+    type Token = I::Token;
+    type Err = I::Err;
+
+    fn register(&mut self, registry: &mut Reactor, token: Self::Token) -> Result<(), Self::Err> {
+        self.inner.register(registry, token)
+    }
+
+    fn deregister(&mut self, registry: &mut Reactor) -> Result<(), Self::Err> {
+        self.inner.deregister(registry)
+    }
+
+    fn on_ready(&mut self) -> Result<(), Self::Err> {
+        self.inner.on_ready()
+    }
+}
@@ -1,3 +1,6 @@
+mod call;
+pub use call::{Call, UnexpectedResponse};
+
 mod callback;
 pub use callback::{callback_fn, Callback, FnCallback};
 
@@ -6,6 +9,7 @@ pub use handler_builder::HandlerBuilder;
 
 pub mod invocation_source;
 pub mod layer;
+pub mod router;
 pub mod server;
 
 pub trait Handler<Arg> {
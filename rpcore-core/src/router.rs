@@ -0,0 +1,242 @@
+//! Dispatches the variants of a request enum to their own handlers.
+//!
+//! ```
+//! # use rpcore_core::router::Router;
+//! # use rpcore_core::{callback_fn, Callback, Handler};
+//! enum Request {
+//!     Add(i64, i64),
+//!     Echo(String),
+//! }
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Response {
+//!     Sum(i64),
+//!     Echo(String),
+//! }
+//!
+//! impl From<i64> for Response {
+//!     fn from(sum: i64) -> Self {
+//!         Response::Sum(sum)
+//!     }
+//! }
+//!
+//! impl From<String> for Response {
+//!     fn from(s: String) -> Self {
+//!         Response::Echo(s)
+//!     }
+//! }
+//!
+//! struct Add;
+//!
+//! impl Handler<(i64, i64)> for Add {
+//!     type Ret = i64;
+//!
+//!     fn handle(&mut self, (a, b): (i64, i64), callback: impl Callback<Ret = i64>) {
+//!         callback.call(a + b);
+//!     }
+//! }
+//!
+//! struct Echo;
+//!
+//! impl Handler<String> for Echo {
+//!     type Ret = String;
+//!
+//!     fn handle(&mut self, s: String, callback: impl Callback<Ret = String>) {
+//!         callback.call(s);
+//!     }
+//! }
+//!
+//! let mut router = Router::<Request, Response>::new()
+//!     .route(
+//!         |req| match req {
+//!             Request::Add(a, b) => Ok((a, b)),
+//!             other => Err(other),
+//!         },
+//!         Add,
+//!     )
+//!     .route(
+//!         |req| match req {
+//!             Request::Echo(s) => Ok(s),
+//!             other => Err(other),
+//!         },
+//!         Echo,
+//!     );
+//!
+//! router.handle(
+//!     Request::Add(1, 2),
+//!     callback_fn(|resp| assert_eq!(resp, Response::Sum(3))),
+//! );
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::{callback_fn, Callback, Handler};
+
+/// A handler that passes every request to the first route that extracts an
+/// argument from it, and converts the return value of the route's handler
+/// into `Resp` with [`Into`].
+///
+/// Routes are tried in the order they were added. Requests that no route
+/// extracts go to the fallback handler set with [`Router::fallback`]. Without
+/// one, their callbacks are dropped, so the caller sees the same error as for
+/// a handler that drops its callback. They are also logged with the `log`
+/// feature.
+pub struct Router<Req, Resp, R = NoRoute<Req, Resp>> {
+    routes: R,
+    _phantom: PhantomData<fn(Req) -> Resp>,
+}
+
+impl<Req, Resp> Default for Router<Req, Resp> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Req, Resp> Router<Req, Resp> {
+    pub const fn new() -> Self {
+        Self {
+            routes: NoRoute(PhantomData),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Req, Resp, R> Router<Req, Resp, R> {
+    /// Adds a route that handles the `Arg`s extracted from requests with
+    /// `handler`.
+    ///
+    /// `extract` returns the request back if it is not for this route.
+    pub fn route<E, H, Arg>(self, extract: E, handler: H) -> Router<Req, Resp, R::Output>
+    where
+        R: Append<Route<E, H, NoRoute<Req, Resp>>>,
+        E: FnMut(Req) -> Result<Arg, Req>,
+        H: Handler<Arg>,
+        H::Ret: Into<Resp>,
+    {
+        let route = Route {
+            extract,
+            handler,
+            next: NoRoute(PhantomData),
+        };
+        Router {
+            routes: self.routes.append(route),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Handles the requests that no route extracts with `handler`.
+    pub fn fallback<H>(self, handler: H) -> Router<Req, Resp, R::Output>
+    where
+        R: Append<H>,
+        H: Handler<Req, Ret = Resp>,
+    {
+        Router {
+            routes: self.routes.append(handler),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Req, Resp, R> Handler<Req> for Router<Req, Resp, R>
+where
+    R: Handler<Req, Ret = Resp>,
+{
+    type Ret = Resp;
+
+    fn handle(&mut self, arg: Req, callback: impl Callback<Ret = Self::Ret>) {
+        self.routes.handle(arg, callback);
+    }
+}
+
+impl<Req, Resp, R: fmt::Debug> fmt::Debug for Router<Req, Resp, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Router").field(&self.routes).finish()
+    }
+}
+
+/// A route of a [`Router`], followed by the routes in `next`.
+pub struct Route<E, H, Next> {
+    extract: E,
+    handler: H,
+    next: Next,
+}
+
+impl<E, H, Next, Req, Arg> Handler<Req> for Route<E, H, Next>
+where
+    E: FnMut(Req) -> Result<Arg, Req>,
+    H: Handler<Arg>,
+    Next: Handler<Req>,
+    H::Ret: Into<Next::Ret> + 'static,
+    Next::Ret: 'static,
+{
+    type Ret = Next::Ret;
+
+    fn handle(&mut self, arg: Req, callback: impl Callback<Ret = Self::Ret>) {
+        match (self.extract)(arg) {
+            Ok(arg) => self.handler.handle(
+                arg,
+                callback_fn(move |ret: H::Ret| callback.call(ret.into())),
+            ),
+            Err(arg) => self.next.handle(arg, callback),
+        }
+    }
+}
+
+impl<E, H: fmt::Debug, Next: fmt::Debug> fmt::Debug for Route<E, H, Next> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Route")
+            .field("handler", &self.handler)
+            .field("next", &self.next)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The end of the routes of a [`Router`] without fallback, it drops the
+/// callback, and logs the request with the `log` feature.
+pub struct NoRoute<Req, Resp>(PhantomData<fn(Req) -> Resp>);
+
+impl<Req, Resp> Handler<Req> for NoRoute<Req, Resp> {
+    type Ret = Resp;
+
+    fn handle(&mut self, _: Req, _: impl Callback<Ret = Self::Ret>) {
+        #[cfg(feature = "log")]
+        log::error!("No route for the {} request.", std::any::type_name::<Req>());
+    }
+}
+
+impl<Req, Resp> fmt::Debug for NoRoute<Req, Resp> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NoRoute")
+    }
+}
+
+/// Puts `T` in place of the [`NoRoute`] at the end of a chain of routes.
+pub trait Append<T> {
+    type Output;
+
+    fn append(self, tail: T) -> Self::Output;
+}
+
+impl<Req, Resp, T> Append<T> for NoRoute<Req, Resp> {
+    type Output = T;
+
+    fn append(self, tail: T) -> Self::Output {
+        tail
+    }
+}
+
+impl<E, H, Next, T> Append<T> for Route<E, H, Next>
+where
+    Next: Append<T>,
+{
+    type Output = Route<E, H, Next::Output>;
+
+    fn append(self, tail: T) -> Self::Output {
+        Route {
+            extract: self.extract,
+            handler: self.handler,
+            next: self.next.append(tail),
+        }
+    }
+}
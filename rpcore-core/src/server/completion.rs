@@ -0,0 +1,375 @@
+//! A completion-based server that drives many invocation sources from one
+//! `io_uring` instance.
+//!
+//! SQ polling is enabled by building the ring with
+//! [`io_uring::Builder::setup_sqpoll`], and CQ busy-polling by the
+//! [`HasPolling`] setting of the server.
+
+use std::error::Error as StdError;
+use std::time::{Duration, Instant};
+use std::{fmt, io, mem};
+
+use io_uring::types::{SubmitArgs, Timespec};
+use io_uring::{cqueue, opcode, IoUring, SubmissionQueue, Submitter};
+
+use crate::invocation_source::completion::Proactor;
+use crate::invocation_source::recv::{Error, TryRecvInvocation};
+use crate::server::settings::{HasHooks, HasPolling};
+use crate::server::{Hooks, IsShuttingDown};
+use crate::{Callback, Handler};
+
+const CHECK_AFTER_POLL_N_TIMES: u32 = 128;
+
+/// The longest time the server blocks waiting for completions before checking
+/// whether it is shutting down.
+const SHUTDOWN_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// [`SHUTDOWN_CHECK_INTERVAL`] for the kernel. It is a static because a
+/// timeout entry points to it until the entry is submitted.
+static SHUTDOWN_CHECK_TIMESPEC: Timespec = Timespec::new()
+    .sec(SHUTDOWN_CHECK_INTERVAL.as_secs())
+    .nsec(SHUTDOWN_CHECK_INTERVAL.subsec_nanos());
+
+/// The `user_data` of the timeout that bounds waiting for completions on
+/// kernels without `IORING_FEAT_EXT_ARG`. Source keys are indices, so they
+/// never reach it.
+const TIMEOUT_USER_DATA: u64 = u64::MAX;
+
+pub struct Server<I, H, S> {
+    ring: IoUring,
+    inv_srcs: Vec<Option<I>>,
+    vacant: Vec<usize>,
+    unsubmitted: Vec<usize>,
+    cqes: Vec<cqueue::Entry>,
+    timeout_pending: bool,
+    pub handler: H,
+    pub settings: S,
+}
+
+impl<I, H, S> Server<I, H, S> {
+    pub fn new(ring: IoUring, handler: H, settings: S) -> Self {
+        Self {
+            ring,
+            inv_srcs: Vec::new(),
+            vacant: Vec::new(),
+            unsubmitted: Vec::new(),
+            cqes: Vec::new(),
+            timeout_pending: false,
+            handler,
+            settings,
+        }
+    }
+
+    pub fn ring(&mut self) -> &mut IoUring {
+        &mut self.ring
+    }
+
+    /// Returns the number of registered invocation sources.
+    pub fn len(&self) -> usize {
+        self.inv_srcs.len() - self.vacant.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Initializes `inv_src`, submits its first request and returns the key
+    /// used as its `user_data`.
+    pub fn add_source<IE, SE, OE>(
+        &mut self,
+        mut inv_src: I,
+    ) -> Result<usize, AddSourceError<IE, SE>>
+    where
+        I: for<'a> Proactor<
+            Submitter<'a>,
+            SubmissionQueue<'a>,
+            cqueue::Entry,
+            u64,
+            InitErr = IE,
+            SubmitErr = SE,
+            OnCompleteErr = OE,
+        >,
+    {
+        let key = match self.vacant.last() {
+            Some(&key) => key,
+            None => self.inv_srcs.len(),
+        };
+
+        inv_src
+            .init(&self.ring.submitter())
+            .map_err(AddSourceError::Init)?;
+        if inv_src
+            .submit(&mut self.ring.submission(), key as u64)
+            .is_err()
+        {
+            // The submission queue is full, make room and try again.
+            self.ring.submit().map_err(AddSourceError::Io)?;
+            inv_src
+                .submit(&mut self.ring.submission(), key as u64)
+                .map_err(AddSourceError::Submit)?;
+        }
+
+        if key == self.inv_srcs.len() {
+            self.inv_srcs.push(Some(inv_src));
+        } else {
+            self.vacant.pop();
+            self.inv_srcs[key] = Some(inv_src);
+        }
+        Ok(key)
+    }
+}
+
+impl<I, H, S, Arg, Cb, IE, SE, OE> Serve<Arg, Cb> for Server<I, H, S>
+where
+    I: for<'a> Proactor<
+            Submitter<'a>,
+            SubmissionQueue<'a>,
+            cqueue::Entry,
+            u64,
+            InitErr = IE,
+            SubmitErr = SE,
+            OnCompleteErr = OE,
+        > + TryRecvInvocation<Arg, Cb>,
+    SE: StdError,
+    OE: StdError,
+    Cb: Callback<Ret = H::Ret>,
+    H: Handler<Arg>,
+    S: HasHooks + HasPolling,
+{
+    fn serve(&mut self, shutdown: &impl IsShuttingDown) {
+        let args = SubmitArgs::new().timespec(&SHUTDOWN_CHECK_TIMESPEC);
+        let has_ext_arg = self.ring.params().is_feature_ext_arg();
+
+        loop {
+            if shutdown.is_shutting_down() || self.is_empty() {
+                self.settings.hooks().on_shutdown();
+                return;
+            }
+
+            self.resubmit();
+            let result = if has_ext_arg {
+                self.ring.submitter().submit_with_args(1, &args)
+            } else {
+                self.arm_timeout();
+                self.ring.submit_and_wait(1)
+            };
+            match result {
+                Ok(_) => {}
+                Err(e) if e.raw_os_error() == Some(libc::ETIME) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => self.settings.hooks().on_error(&e),
+            }
+
+            if !self.complete() {
+                continue;
+            }
+            let poll_dur = match self.settings.polling() {
+                Some(dur) => *dur,
+                None => continue,
+            };
+            let mut poll_until = Instant::now() + poll_dur;
+            let mut i = 0;
+            loop {
+                i = (i + 1) % CHECK_AFTER_POLL_N_TIMES;
+                if i == 0 {
+                    if Instant::now() >= poll_until {
+                        break;
+                    }
+
+                    if shutdown.is_shutting_down() {
+                        self.settings.hooks().on_shutdown();
+                        return;
+                    }
+                }
+
+                if !self.ring.submission().is_empty() || !self.unsubmitted.is_empty() {
+                    self.resubmit();
+                    if let Err(e) = self.ring.submit() {
+                        self.settings.hooks().on_error(&e);
+                    }
+                }
+                if self.complete() {
+                    poll_until = Instant::now() + poll_dur;
+                }
+                if self.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl<I, H, S> Server<I, H, S>
+where
+    S: HasHooks,
+{
+    /// Processes every entry in the completion queue: completes the source it
+    /// belongs to, handles the invocations now available from that source, and
+    /// submits its next request. Returns whether any invocation was handled.
+    fn complete<Arg, Cb, IE, SE, OE>(&mut self) -> bool
+    where
+        I: for<'a> Proactor<
+                Submitter<'a>,
+                SubmissionQueue<'a>,
+                cqueue::Entry,
+                u64,
+                InitErr = IE,
+                SubmitErr = SE,
+                OnCompleteErr = OE,
+            > + TryRecvInvocation<Arg, Cb>,
+        SE: StdError,
+        OE: StdError,
+        Cb: Callback<Ret = H::Ret>,
+        H: Handler<Arg>,
+    {
+        let mut cqes = mem::take(&mut self.cqes);
+        cqes.extend(self.ring.completion());
+
+        let mut handled = false;
+        for cqe in cqes.drain(..) {
+            if cqe.user_data() == TIMEOUT_USER_DATA {
+                self.timeout_pending = false;
+                continue;
+            }
+
+            let key = cqe.user_data() as usize;
+            let Some(Some(inv_src)) = self.inv_srcs.get_mut(key) else {
+                continue;
+            };
+
+            if let Err(e) = inv_src.on_complete(cqe) {
+                self.settings.hooks().on_error(&e);
+            }
+
+            let closed = loop {
+                let inv = match inv_src.try_recv() {
+                    Ok(inv) => inv,
+                    Err(e) if e.is_empty() => break false,
+                    Err(e) if e.is_interrupted() => continue,
+                    Err(e) if e.is_closed() => {
+                        self.settings.hooks().on_error(&e);
+                        break true;
+                    }
+                    Err(e) => {
+                        self.settings.hooks().on_error(&e);
+                        continue;
+                    }
+                };
+
+                self.handler.handle(inv.arg, inv.callback);
+                handled = true;
+            };
+
+            if closed {
+                // No request of the source is in flight, so it can be dropped.
+                self.inv_srcs[key] = None;
+                self.vacant.push(key);
+            } else if inv_src
+                .submit(&mut self.ring.submission(), key as u64)
+                .is_err()
+            {
+                self.unsubmitted.push(key);
+            }
+        }
+
+        self.cqes = cqes;
+        handled
+    }
+
+    /// Queues a timeout that completes after [`SHUTDOWN_CHECK_INTERVAL`], unless
+    /// one is already pending, so that waiting for a completion cannot block
+    /// past a shutdown.
+    fn arm_timeout(&mut self) {
+        if self.timeout_pending {
+            return;
+        }
+
+        let entry = opcode::Timeout::new(&SHUTDOWN_CHECK_TIMESPEC)
+            .build()
+            .user_data(TIMEOUT_USER_DATA);
+        // SAFETY: The timespec is a static, so it outlives the entry.
+        if unsafe { self.ring.submission().push(&entry) }.is_err() {
+            // The submission queue is full, make room and try again.
+            if let Err(e) = self.ring.submit() {
+                self.settings.hooks().on_error(&e);
+            }
+            if let Err(e) = unsafe { self.ring.submission().push(&entry) } {
+                self.settings.hooks().on_error(&e);
+                return;
+            }
+        }
+        self.timeout_pending = true;
+    }
+
+    /// Submits the next request of the sources that did not fit into the
+    /// submission queue.
+    fn resubmit<IE, SE, OE>(&mut self)
+    where
+        I: for<'a> Proactor<
+            Submitter<'a>,
+            SubmissionQueue<'a>,
+            cqueue::Entry,
+            u64,
+            InitErr = IE,
+            SubmitErr = SE,
+            OnCompleteErr = OE,
+        >,
+        SE: StdError,
+    {
+        if self.unsubmitted.is_empty() {
+            return;
+        }
+        if let Err(e) = self.ring.submit() {
+            self.settings.hooks().on_error(&e);
+        }
+
+        while let Some(&key) = self.unsubmitted.last() {
+            let Some(Some(inv_src)) = self.inv_srcs.get_mut(key) else {
+                self.unsubmitted.pop();
+                continue;
+            };
+            match inv_src.submit(&mut self.ring.submission(), key as u64) {
+                Ok(()) => {
+                    self.unsubmitted.pop();
+                }
+                Err(e) => {
+                    self.settings.hooks().on_error(&e);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+pub trait Serve<Arg, Cb> {
+    fn serve(&mut self, shutdown: &impl IsShuttingDown);
+}
+
+/// An error that occurred while adding an invocation source to a [`Server`].
+#[derive(Debug)]
+pub enum AddSourceError<IE, SE> {
+    Init(IE),
+    Submit(SE),
+    Io(io::Error),
+}
+
+impl<IE, SE> fmt::Display for AddSourceError<IE, SE>
+where
+    IE: fmt::Display,
+    SE: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Init(e) => write!(f, "failed to init invocation source: {e}"),
+            Self::Submit(e) => write!(f, "failed to submit: {e}"),
+            Self::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<IE, SE> StdError for AddSourceError<IE, SE>
+where
+    IE: StdError,
+    SE: StdError,
+{
+}
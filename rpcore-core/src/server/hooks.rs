@@ -5,6 +5,11 @@ pub trait Hooks {
 
     #[allow(unused_variables)]
     fn on_error(&mut self, e: &dyn Error) {}
+
+    /// Called by a graceful shutdown once the queued invocations are handled,
+    /// with the number of callbacks abandoned in flight at the deadline.
+    #[allow(unused_variables)]
+    fn on_drained(&mut self, abandoned: usize) {}
 }
 
 impl Hooks for () {}
@@ -0,0 +1,78 @@
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::Callback;
+
+/// Counts the callbacks that have been handed to a handler and not called or
+/// dropped yet.
+#[derive(Default)]
+pub(crate) struct Inflight {
+    count: Mutex<usize>,
+    cvar: Condvar,
+}
+
+impl Inflight {
+    fn count(&self) -> MutexGuard<'_, usize> {
+        self.count
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Wraps `callback` so that it is counted until it is called or dropped.
+    pub(crate) fn track<Cb: Callback>(self: &Arc<Self>, callback: Cb) -> Tracked<Cb> {
+        *self.count() += 1;
+        Tracked {
+            callback,
+            _guard: Guard {
+                inflight: Arc::clone(self),
+            },
+        }
+    }
+
+    /// Waits until no callback is in flight or `timeout` elapses, and returns
+    /// the number of callbacks still in flight.
+    pub(crate) fn wait(&self, timeout: Duration) -> usize {
+        let deadline = Instant::now() + timeout;
+        let mut count = self.count();
+        while *count > 0 {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            count = self
+                .cvar
+                .wait_timeout(count, deadline - now)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+        *count
+    }
+}
+
+pub(crate) struct Tracked<Cb> {
+    callback: Cb,
+    _guard: Guard,
+}
+
+impl<Cb: Callback> Callback for Tracked<Cb> {
+    type Ret = Cb::Ret;
+
+    fn call(self, out: Self::Ret) {
+        let Self { callback, _guard } = self;
+        callback.call(out);
+    }
+}
+
+struct Guard {
+    inflight: Arc<Inflight>,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let mut count = self.inflight.count();
+        *count -= 1;
+        if *count == 0 {
+            self.inflight.cvar.notify_all();
+        }
+    }
+}
@@ -1,11 +1,18 @@
 mod hooks;
 pub use hooks::Hooks;
 
+mod inflight;
+
 mod shutdown;
-pub use shutdown::{IsShuttingDown, Shutdown, ShutdownBool};
+pub use shutdown::{IsShuttingDown, Shutdown, ShutdownBool, WakeGuard, WakeOnShutdown};
 
 mod token;
-pub use token::{GetToken, SetToken, SyncTokenAllocator, Token, UnsyncTokenAllocator, WithToken};
+pub use token::{
+    AllocToken, GetToken, SetToken, SyncTokenAllocator, Token, UnsyncTokenAllocator, WithToken,
+};
 
+#[cfg(feature = "io-uring")]
+pub mod completion;
+pub mod multiplex;
 pub mod settings;
 pub mod singleplex;
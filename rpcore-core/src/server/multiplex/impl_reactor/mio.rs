@@ -0,0 +1,60 @@
+use std::io;
+use std::time::Duration;
+
+use mio::{Events, Poll, Registry, Token};
+
+use crate::invocation_source::readiness::Reactor;
+
+/// A [`Reactor`] backed by a `mio` [`Poll`] instance.
+pub struct MioReactor {
+    poll: Poll,
+    registry: Registry,
+    events: Events,
+}
+
+impl MioReactor {
+    /// Creates a new poll instance that reports at most `capacity` events per
+    /// wait.
+    pub fn new(capacity: usize) -> io::Result<Self> {
+        Self::with_poll(Poll::new()?, capacity)
+    }
+
+    pub fn with_poll(poll: Poll, capacity: usize) -> io::Result<Self> {
+        assert!(capacity > 0, "Capacity must be greater than 0.");
+
+        Ok(Self {
+            registry: poll.registry().try_clone()?,
+            poll,
+            events: Events::with_capacity(capacity),
+        })
+    }
+
+    pub fn into_inner(self) -> Poll {
+        self.poll
+    }
+}
+
+impl Reactor for MioReactor {
+    type Registry = Registry;
+    type Token = Token;
+    type Err = io::Error;
+
+    fn registry(&mut self) -> &mut Self::Registry {
+        &mut self.registry
+    }
+
+    fn token(key: usize) -> Self::Token {
+        Token(key)
+    }
+
+    fn wait(&mut self, ready: &mut Vec<usize>, timeout: Option<Duration>) -> Result<(), Self::Err> {
+        match self.poll.poll(&mut self.events, timeout) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => return Ok(()),
+            Err(e) => return Err(e),
+        }
+
+        ready.extend(self.events.iter().map(|event| event.token().0));
+        Ok(())
+    }
+}
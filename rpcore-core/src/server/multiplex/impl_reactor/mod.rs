@@ -0,0 +1,9 @@
+#[cfg(feature = "nix")]
+mod nix;
+#[cfg(feature = "nix")]
+pub use self::nix::EpollReactor;
+
+#[cfg(feature = "mio")]
+mod mio;
+#[cfg(feature = "mio")]
+pub use self::mio::MioReactor;
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use nix::errno::Errno;
+use nix::sys::epoll::{Epoll, EpollCreateFlags, EpollEvent, EpollTimeout};
+
+use crate::invocation_source::readiness::Reactor;
+
+/// A [`Reactor`] backed by a `nix` [`Epoll`] instance.
+pub struct EpollReactor {
+    epoll: Epoll,
+    events: Vec<EpollEvent>,
+}
+
+impl EpollReactor {
+    /// Creates a new epoll instance that reports at most `capacity` events
+    /// per wait.
+    pub fn new(capacity: usize) -> nix::Result<Self> {
+        let epoll = Epoll::new(EpollCreateFlags::EPOLL_CLOEXEC)?;
+        Ok(Self::with_epoll(epoll, capacity))
+    }
+
+    pub fn with_epoll(epoll: Epoll, capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0.");
+
+        Self {
+            epoll,
+            events: vec![EpollEvent::empty(); capacity],
+        }
+    }
+
+    pub fn into_inner(self) -> Epoll {
+        self.epoll
+    }
+}
+
+impl Reactor for EpollReactor {
+    type Registry = Epoll;
+    type Token = u64;
+    type Err = nix::Error;
+
+    fn registry(&mut self) -> &mut Self::Registry {
+        &mut self.epoll
+    }
+
+    fn token(key: usize) -> Self::Token {
+        key as u64
+    }
+
+    fn wait(&mut self, ready: &mut Vec<usize>, timeout: Option<Duration>) -> Result<(), Self::Err> {
+        let timeout = timeout.map(|dur| EpollTimeout::try_from(dur).unwrap_or(EpollTimeout::MAX));
+        let n = match self.epoll.wait(&mut self.events, timeout) {
+            Ok(n) => n,
+            Err(Errno::EINTR) => 0,
+            Err(e) => return Err(e),
+        };
+
+        ready.extend(self.events[..n].iter().map(|event| event.data() as usize));
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+//! A readiness-based server that drives many invocation sources from one
+//! [`Reactor`].
+
+use std::mem;
+use std::time::{Duration, Instant};
+
+use crate::invocation_source::readiness::{Accept, EventSource, Reactor};
+use crate::invocation_source::recv::{Error, TryRecvInvocation};
+use crate::server::settings::{HasHooks, HasPolling};
+use crate::server::{Hooks, IsShuttingDown};
+use crate::{Callback, Handler};
+
+mod impl_reactor;
+#[cfg(feature = "nix")]
+pub use impl_reactor::EpollReactor;
+#[cfg(feature = "mio")]
+pub use impl_reactor::MioReactor;
+
+const CHECK_AFTER_POLL_N_TIMES: u32 = 128;
+
+/// The longest time the server blocks in [`Reactor::wait`] before checking
+/// whether it is shutting down.
+const SHUTDOWN_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The key the acceptor is registered under in [`ServeAccepting`].
+const ACCEPTOR_KEY: usize = usize::MAX;
+
+pub struct Server<R, I, H, S> {
+    reactor: R,
+    inv_srcs: Vec<Option<I>>,
+    vacant: Vec<usize>,
+    ready: Vec<usize>,
+    pub handler: H,
+    pub settings: S,
+}
+
+impl<R, I, H, S> Server<R, I, H, S> {
+    pub fn new(reactor: R, handler: H, settings: S) -> Self {
+        Self {
+            reactor,
+            inv_srcs: Vec::new(),
+            vacant: Vec::new(),
+            ready: Vec::new(),
+            handler,
+            settings,
+        }
+    }
+
+    pub fn reactor(&mut self) -> &mut R {
+        &mut self.reactor
+    }
+
+    /// Returns the number of registered invocation sources.
+    pub fn len(&self) -> usize {
+        self.inv_srcs.len() - self.vacant.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<R, I, H, S> Server<R, I, H, S>
+where
+    R: Reactor,
+    I: EventSource<R::Registry, Token = R::Token>,
+{
+    /// Registers `inv_src` with the reactor and returns the key it is
+    /// registered under.
+    pub fn add_source(&mut self, mut inv_src: I) -> Result<usize, I::Err> {
+        let key = match self.vacant.last() {
+            Some(&key) => key,
+            None => self.inv_srcs.len(),
+        };
+        inv_src.register(self.reactor.registry(), R::token(key))?;
+
+        if key == self.inv_srcs.len() {
+            self.inv_srcs.push(Some(inv_src));
+        } else {
+            self.vacant.pop();
+            self.inv_srcs[key] = Some(inv_src);
+        }
+        Ok(key)
+    }
+
+    /// Deregisters the source under `key` and returns it.
+    ///
+    /// The source is removed even if deregistration fails.
+    pub fn remove_source(&mut self, key: usize) -> Option<(I, Result<(), I::Err>)> {
+        let mut inv_src = self.inv_srcs.get_mut(key)?.take()?;
+        self.vacant.push(key);
+
+        let result = inv_src.deregister(self.reactor.registry());
+        Some((inv_src, result))
+    }
+}
+
+impl<R, I, H, S, Arg, Cb> Serve<Arg, Cb> for Server<R, I, H, S>
+where
+    R: Reactor,
+    I: EventSource<R::Registry, Token = R::Token> + TryRecvInvocation<Arg, Cb>,
+    I::Err: std::error::Error,
+    Cb: Callback<Ret = H::Ret>,
+    H: Handler<Arg>,
+    S: HasHooks + HasPolling,
+{
+    fn serve(&mut self, shutdown: &impl IsShuttingDown) {
+        self.run(shutdown, false, |this, key| this.drain(key, true));
+    }
+}
+
+impl<R, I, H, S, Arg, Cb, A> ServeAccepting<Arg, Cb, A> for Server<R, I, H, S>
+where
+    R: Reactor,
+    I: EventSource<R::Registry, Token = R::Token> + TryRecvInvocation<Arg, Cb>,
+    I::Err: std::error::Error,
+    A: EventSource<R::Registry, Token = R::Token> + Accept<Source = I>,
+    A::Err: std::error::Error,
+    A::AcceptErr: std::error::Error,
+    Cb: Callback<Ret = H::Ret>,
+    H: Handler<Arg>,
+    S: HasHooks + HasPolling,
+{
+    fn serve_accepting(&mut self, acceptor: &mut A, shutdown: &impl IsShuttingDown) {
+        if let Err(e) = acceptor.register(self.reactor.registry(), R::token(ACCEPTOR_KEY)) {
+            self.settings.hooks().on_error(&e);
+            self.settings.hooks().on_shutdown();
+            return;
+        }
+
+        self.run(shutdown, true, |this, key| {
+            if key != ACCEPTOR_KEY {
+                return this.drain(key, true);
+            }
+
+            if let Err(e) = acceptor.on_ready() {
+                this.settings.hooks().on_error(&e);
+            }
+            // The acceptor is edge-triggered, so it is drained even past the
+            // errors of single connections.
+            loop {
+                match acceptor.try_accept() {
+                    Ok(Some(inv_src)) => {
+                        if let Err(e) = this.add_source(inv_src) {
+                            this.settings.hooks().on_error(&e);
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(e) => this.settings.hooks().on_error(&e),
+                }
+            }
+            false
+        });
+
+        if let Err(e) = acceptor.deregister(self.reactor.registry()) {
+            self.settings.hooks().on_error(&e);
+        }
+    }
+}
+
+impl<R, I, H, S> Server<R, I, H, S>
+where
+    R: Reactor,
+    I: EventSource<R::Registry, Token = R::Token>,
+    I::Err: std::error::Error,
+    S: HasHooks + HasPolling,
+{
+    /// Runs the serve loop, calling `on_ready` with the key of every ready
+    /// source. Unless `keep_open` is set, returns once all sources are closed.
+    fn run<Arg, Cb>(
+        &mut self,
+        shutdown: &impl IsShuttingDown,
+        keep_open: bool,
+        mut on_ready: impl FnMut(&mut Self, usize) -> bool,
+    ) where
+        I: TryRecvInvocation<Arg, Cb>,
+        Cb: Callback<Ret = H::Ret>,
+        H: Handler<Arg>,
+    {
+        loop {
+            if shutdown.is_shutting_down() || (!keep_open && self.is_empty()) {
+                self.settings.hooks().on_shutdown();
+                return;
+            }
+
+            let mut ready = mem::take(&mut self.ready);
+            ready.clear();
+            if let Err(e) = self.reactor.wait(&mut ready, Some(SHUTDOWN_CHECK_INTERVAL)) {
+                self.settings.hooks().on_error(&e);
+            }
+
+            let mut handled = false;
+            for &key in &ready {
+                handled |= on_ready(self, key);
+            }
+            self.ready = ready;
+
+            if !handled {
+                continue;
+            }
+            let poll_dur = match self.settings.polling() {
+                Some(dur) => *dur,
+                None => continue,
+            };
+            let mut poll_until = Instant::now() + poll_dur;
+            let mut i = 0;
+            loop {
+                i = (i + 1) % CHECK_AFTER_POLL_N_TIMES;
+                if i == 0 {
+                    if Instant::now() >= poll_until {
+                        break;
+                    }
+
+                    if shutdown.is_shutting_down() {
+                        self.settings.hooks().on_shutdown();
+                        return;
+                    }
+                }
+
+                let mut handled = false;
+                for key in 0..self.inv_srcs.len() {
+                    handled |= self.drain(key, false);
+                }
+                if self.is_empty() {
+                    break;
+                }
+                if handled {
+                    poll_until = Instant::now() + poll_dur;
+                }
+            }
+        }
+    }
+}
+
+impl<R, I, H, S> Server<R, I, H, S>
+where
+    R: Reactor,
+    I: EventSource<R::Registry, Token = R::Token>,
+    I::Err: std::error::Error,
+    S: HasHooks,
+{
+    /// Handles every invocation currently available from the source under
+    /// `key`, and removes the source once it is closed. Returns whether any
+    /// invocation was handled.
+    fn drain<Arg, Cb>(&mut self, key: usize, is_ready: bool) -> bool
+    where
+        I: TryRecvInvocation<Arg, Cb>,
+        Cb: Callback<Ret = H::Ret>,
+        H: Handler<Arg>,
+    {
+        let Some(Some(inv_src)) = self.inv_srcs.get_mut(key) else {
+            return false;
+        };
+
+        if is_ready {
+            if let Err(e) = inv_src.on_ready() {
+                self.settings.hooks().on_error(&e);
+            }
+        }
+
+        let mut handled = false;
+        let closed = loop {
+            let inv = match inv_src.try_recv() {
+                Ok(inv) => inv,
+                Err(e) if e.is_empty() => break false,
+                Err(e) if e.is_interrupted() => continue,
+                Err(e) if e.is_closed() => {
+                    self.settings.hooks().on_error(&e);
+                    break true;
+                }
+                Err(e) => {
+                    self.settings.hooks().on_error(&e);
+                    continue;
+                }
+            };
+
+            self.handler.handle(inv.arg, inv.callback);
+            handled = true;
+        };
+
+        if closed {
+            if let Some((_, Err(e))) = self.remove_source(key) {
+                self.settings.hooks().on_error(&e);
+            }
+        }
+        handled
+    }
+}
+
+pub trait Serve<Arg, Cb> {
+    fn serve(&mut self, shutdown: &impl IsShuttingDown);
+}
+
+pub trait ServeAccepting<Arg, Cb, A> {
+    /// Serves like [`Serve::serve`], and also adds every invocation source
+    /// accepted from `acceptor`, e.g. the connections accepted by a listener.
+    ///
+    /// Unlike [`Serve::serve`], it keeps serving when no source is left.
+    fn serve_accepting(&mut self, acceptor: &mut A, shutdown: &impl IsShuttingDown);
+}
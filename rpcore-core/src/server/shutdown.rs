@@ -1,5 +1,6 @@
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
 
 pub trait Shutdown {
     fn shutdown(&self);
@@ -9,9 +10,32 @@ pub trait IsShuttingDown {
     fn is_shutting_down(&self) -> bool;
 }
 
+/// A shutdown signal that can wake up a server blocked waiting for
+/// invocations.
+pub trait WakeOnShutdown: IsShuttingDown {
+    /// Unregisters the wake-up when dropped.
+    type Guard;
+
+    /// Calls `wake` once on shutdown, or right away if already shutting down,
+    /// unless the returned guard is dropped first.
+    fn wake_on_shutdown(&self, wake: impl FnOnce() + Send + 'static) -> Self::Guard;
+}
+
 #[derive(Default, Clone)]
 pub struct ShutdownBool {
-    bool: Arc<AtomicBool>,
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    bool: AtomicBool,
+    wakers: Mutex<Wakers>,
+}
+
+#[derive(Default)]
+struct Wakers {
+    next_id: u64,
+    wakers: HashMap<u64, Box<dyn FnOnce() + Send>>,
 }
 
 impl ShutdownBool {
@@ -20,14 +44,67 @@ impl ShutdownBool {
     }
 }
 
+impl Inner {
+    fn wakers(&self) -> MutexGuard<'_, Wakers> {
+        self.wakers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
 impl Shutdown for ShutdownBool {
     fn shutdown(&self) {
-        self.bool.store(true, Ordering::Release);
+        self.inner.bool.store(true, Ordering::Release);
+
+        // The wakers are taken after setting the flag, so that a waker
+        // registered concurrently is either taken here or called right away.
+        let wakers = std::mem::take(&mut self.inner.wakers().wakers);
+        for wake in wakers.into_values() {
+            wake();
+        }
     }
 }
 
 impl IsShuttingDown for ShutdownBool {
     fn is_shutting_down(&self) -> bool {
-        self.bool.load(Ordering::Acquire)
+        self.inner.bool.load(Ordering::Acquire)
+    }
+}
+
+impl WakeOnShutdown for ShutdownBool {
+    type Guard = WakeGuard;
+
+    fn wake_on_shutdown(&self, wake: impl FnOnce() + Send + 'static) -> WakeGuard {
+        let mut wakers = self.inner.wakers();
+        if self.is_shutting_down() {
+            drop(wakers);
+            wake();
+            return WakeGuard {
+                inner: Weak::new(),
+                id: 0,
+            };
+        }
+
+        let id = wakers.next_id;
+        wakers.next_id += 1;
+        wakers.wakers.insert(id, Box::new(wake));
+        WakeGuard {
+            inner: Arc::downgrade(&self.inner),
+            id,
+        }
+    }
+}
+
+/// Unregisters a wake-up of a [`ShutdownBool`] when dropped.
+pub struct WakeGuard {
+    inner: Weak<Inner>,
+    id: u64,
+}
+
+impl Drop for WakeGuard {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.upgrade() {
+            inner.wakers().wakers.remove(&self.id);
+        }
     }
 }
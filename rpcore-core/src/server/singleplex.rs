@@ -1,6 +1,8 @@
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crate::invocation_source::recv::{Error, RecvInvocation, TryRecvInvocation};
+use crate::server::inflight::Inflight;
 use crate::server::settings::{HasHooks, HasPolling};
 use crate::server::{Hooks, IsShuttingDown};
 use crate::{Callback, Handler, Invocation};
@@ -86,6 +88,15 @@ where
 {
 }
 
+impl<I, H, S, Arg, Cb> ServeGraceful<Arg, Cb> for Server<I, H, S>
+where
+    I: TryRecvInvocation<Arg, Cb>,
+    Cb: Callback<Ret = Self::Ret>,
+    H: Handler<Arg>,
+    S: HasHooks,
+{
+}
+
 pub trait Serve<Arg, Cb>: RecvInvocation<Arg, Cb> + Handler<Arg> + HasHooks
 where
     Cb: Callback<Ret = Self::Ret>,
@@ -99,6 +110,7 @@ where
 
             let inv = match self.recv() {
                 Ok(inv) => inv,
+                Err(e) if e.is_interrupted() => continue,
                 Err(e) if e.is_closed() => {
                     self.hooks().on_error(&e);
                     self.hooks().on_shutdown();
@@ -129,6 +141,7 @@ where
 
             let inv = match self.recv() {
                 Ok(inv) => inv,
+                Err(e) if e.is_interrupted() => continue,
                 Err(e) if e.is_closed() => {
                     self.hooks().on_error(&e);
                     self.hooks().on_shutdown();
@@ -164,6 +177,8 @@ where
                 let inv = match self.try_recv() {
                     Ok(inv) => inv,
                     Err(e) if e.is_empty() => continue,
+                    // Checks for shutdown in the outer loop.
+                    Err(e) if e.is_interrupted() => break,
                     Err(e) if e.is_closed() => {
                         self.hooks().on_error(&e);
                         self.hooks().on_shutdown();
@@ -186,3 +201,57 @@ where
         }
     }
 }
+
+pub trait ServeGraceful<Arg, Cb>: TryRecvInvocation<Arg, Cb> + Handler<Arg> + HasHooks
+where
+    Cb: Callback<Ret = Self::Ret>,
+{
+    /// Serves like [`Serve::serve`], then shuts down gracefully: stops
+    /// receiving, handles the invocations already queued, and waits up to
+    /// `grace` for the callbacks in flight to be called or dropped.
+    ///
+    /// The number of callbacks still in flight at the deadline is reported to
+    /// [`Hooks::on_drained`].
+    fn serve_graceful(&mut self, shutdown: &impl IsShuttingDown, grace: Duration) {
+        let inflight = Arc::new(Inflight::default());
+
+        loop {
+            if shutdown.is_shutting_down() {
+                break;
+            }
+
+            let inv = match self.recv() {
+                Ok(inv) => inv,
+                Err(e) if e.is_interrupted() => continue,
+                Err(e) if e.is_closed() => {
+                    self.hooks().on_error(&e);
+                    break;
+                }
+                Err(e) => {
+                    self.hooks().on_error(&e);
+                    continue;
+                }
+            };
+
+            self.handle(inv.arg, inflight.track(inv.callback));
+        }
+
+        loop {
+            let inv = match self.try_recv() {
+                Ok(inv) => inv,
+                Err(e) if e.is_empty() || e.is_closed() => break,
+                Err(e) if e.is_interrupted() => continue,
+                Err(e) => {
+                    self.hooks().on_error(&e);
+                    continue;
+                }
+            };
+
+            self.handle(inv.arg, inflight.track(inv.callback));
+        }
+
+        let abandoned = inflight.wait(grace);
+        self.hooks().on_drained(abandoned);
+        self.hooks().on_shutdown();
+    }
+}
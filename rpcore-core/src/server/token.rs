@@ -1,4 +1,5 @@
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 /// Token is used to distinguish requests from different clients, internally
 /// represented as a `usize`.
@@ -77,6 +78,36 @@ impl UnsyncTokenAllocator {
     }
 }
 
+/// Allocates unique [`Token`]s, implemented by both [`SyncTokenAllocator`] and
+/// [`UnsyncTokenAllocator`].
+pub trait AllocToken {
+    fn alloc_token(&mut self) -> Option<Token>;
+}
+
+impl AllocToken for UnsyncTokenAllocator {
+    fn alloc_token(&mut self) -> Option<Token> {
+        self.alloc()
+    }
+}
+
+impl AllocToken for SyncTokenAllocator {
+    fn alloc_token(&mut self) -> Option<Token> {
+        self.alloc()
+    }
+}
+
+impl AllocToken for &SyncTokenAllocator {
+    fn alloc_token(&mut self) -> Option<Token> {
+        self.alloc()
+    }
+}
+
+impl AllocToken for Arc<SyncTokenAllocator> {
+    fn alloc_token(&mut self) -> Option<Token> {
+        self.alloc()
+    }
+}
+
 pub trait GetToken {
     fn token(&self) -> Token;
 }
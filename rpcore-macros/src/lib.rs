@@ -0,0 +1,62 @@
+mod service;
+
+use proc_macro::TokenStream;
+use syn::parse_macro_input;
+
+/// Defines an RPC service from a trait of methods.
+///
+/// For a trait `Foo`, this generates, next to the trait itself:
+///
+/// - `FooRequest`, an enum with one struct variant per method holding its
+///   arguments.
+/// - `FooResponse`, an enum with one tuple variant per method holding its
+///   return value.
+/// - `FooHandler<T>`, an `rpcore::Handler` of `FooRequest` that calls the
+///   matching method of `T: Foo`.
+/// - `FooClient<C>`, with one method per RPC that calls through any
+///   `rpcore::Call` of `FooRequest`, such as an `MpscClient` or a
+///   `StreamClient`. A call fails with `rpcore::UnexpectedResponse` if the
+///   server returns the response of another method, so the error of `C` must
+///   convert from it.
+///
+/// Methods must take `&self` or `&mut self`, and arguments must be plain
+/// identifiers. Variants are named after their method in upper camel case.
+///
+/// `derive(..)` adds derives to both enums, e.g. to serialize them with serde:
+/// `#[rpcore::service(derive(Debug, Serialize, Deserialize))]`.
+///
+/// The generated code refers to `::rpcore`, so the crate using this macro must
+/// depend on `rpcore`.
+///
+/// ```
+/// # use rpcore::{callback_fn, Handler};
+/// #[rpcore::service(derive(Debug))]
+/// trait Calculator {
+///     fn add(&mut self, a: i64, b: i64) -> i64;
+/// }
+///
+/// struct Adder;
+///
+/// impl Calculator for Adder {
+///     fn add(&mut self, a: i64, b: i64) -> i64 {
+///         a + b
+///     }
+/// }
+///
+/// let mut handler = CalculatorHandler::new(Adder);
+/// let req = CalculatorRequest::Add { a: 1, b: 2 };
+/// handler.handle(req, callback_fn(|resp| match resp {
+///     CalculatorResponse::Add(sum) => assert_eq!(sum, 3),
+/// }));
+/// ```
+#[proc_macro_attribute]
+pub fn service(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = service::Args::default();
+    let parser = syn::meta::parser(|meta| args.parse(meta));
+    parse_macro_input!(attr with parser);
+
+    let item = parse_macro_input!(item as syn::ItemTrait);
+    service::expand(&args, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,301 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{Attribute, FnArg, Ident, ItemTrait, Pat, Path, ReturnType, TraitItem, Type};
+
+/// The arguments of `#[service(..)]`.
+#[derive(Default)]
+pub struct Args {
+    derives: Vec<Path>,
+}
+
+impl Args {
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("derive") {
+            meta.parse_nested_meta(|derive| {
+                self.derives.push(derive.path);
+                Ok(())
+            })
+        } else {
+            Err(meta.error("unsupported service argument"))
+        }
+    }
+}
+
+/// A method of the service trait.
+struct Method {
+    docs: Vec<Attribute>,
+    ident: Ident,
+    variant: Ident,
+    args: Vec<(Ident, Type)>,
+    ret: Type,
+}
+
+impl Method {
+    fn from_item(item: &TraitItem) -> syn::Result<Self> {
+        let TraitItem::Fn(item) = item else {
+            return Err(syn::Error::new(
+                item.span(),
+                "a service can only contain methods",
+            ));
+        };
+        let sig = &item.sig;
+
+        if let Some(asyncness) = sig.asyncness {
+            return Err(syn::Error::new(
+                asyncness.span(),
+                "service methods cannot be async",
+            ));
+        }
+        if !sig.generics.params.is_empty() {
+            return Err(syn::Error::new(
+                sig.generics.span(),
+                "service methods cannot be generic",
+            ));
+        }
+
+        let mut inputs = sig.inputs.iter();
+        match inputs.next() {
+            Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => {}
+            _ => {
+                return Err(syn::Error::new(
+                    sig.span(),
+                    "service methods must take `&self` or `&mut self`",
+                ))
+            }
+        }
+
+        let args = inputs
+            .map(|input| match input {
+                FnArg::Typed(arg) => match &*arg.pat {
+                    Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                        Ok((pat.ident.clone(), (*arg.ty).clone()))
+                    }
+                    pat => Err(syn::Error::new(
+                        pat.span(),
+                        "service arguments must be identifiers",
+                    )),
+                },
+                FnArg::Receiver(receiver) => {
+                    Err(syn::Error::new(receiver.span(), "unexpected receiver"))
+                }
+            })
+            .collect::<syn::Result<_>>()?;
+
+        let ret = match &sig.output {
+            ReturnType::Default => syn::parse_quote!(()),
+            ReturnType::Type(_, ty) => (**ty).clone(),
+        };
+
+        Ok(Self {
+            docs: item
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc"))
+                .cloned()
+                .collect(),
+            variant: to_variant(&sig.ident),
+            ident: sig.ident.clone(),
+            args,
+            ret,
+        })
+    }
+}
+
+/// Converts a method name to a variant name, e.g. `get_value` to `GetValue`.
+fn to_variant(ident: &Ident) -> Ident {
+    let name: String = ident
+        .unraw()
+        .to_string()
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    Ident::new(&name, ident.span())
+}
+
+pub fn expand(args: &Args, item: ItemTrait) -> syn::Result<TokenStream> {
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            item.generics.span(),
+            "services cannot be generic",
+        ));
+    }
+
+    let methods = item
+        .items
+        .iter()
+        .map(Method::from_item)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let vis = &item.vis;
+    let service = &item.ident;
+    let request = format_ident!("{}Request", service);
+    let response = format_ident!("{}Response", service);
+    let handler = format_ident!("{}Handler", service);
+    let client = format_ident!("{}Client", service);
+
+    let derives = &args.derives;
+    let derive = (!derives.is_empty()).then(|| quote!(#[derive(#(#derives),*)]));
+
+    let request_variants = methods.iter().map(|method| {
+        let Method {
+            docs,
+            variant,
+            args,
+            ..
+        } = method;
+        let (names, types): (Vec<_>, Vec<_>) = args.iter().cloned().unzip();
+        quote! {
+            #(#docs)*
+            #variant { #(#names: #types),* }
+        }
+    });
+
+    let response_variants = methods.iter().map(|method| {
+        let Method {
+            docs, variant, ret, ..
+        } = method;
+        quote! {
+            #(#docs)*
+            #variant(#ret)
+        }
+    });
+
+    let dispatch_arms = methods.iter().map(|method| {
+        let Method {
+            ident,
+            variant,
+            args,
+            ..
+        } = method;
+        let names: Vec<_> = args.iter().map(|(name, _)| name).collect();
+        quote! {
+            #request::#variant { #(#names),* } => {
+                #response::#variant(<T as #service>::#ident(&mut self.inner, #(#names),*))
+            }
+        }
+    });
+
+    let client_methods = methods.iter().map(|method| {
+        let Method {
+            docs,
+            ident,
+            variant,
+            args,
+            ret,
+        } = method;
+        let (names, types): (Vec<_>, Vec<_>) = args.iter().cloned().unzip();
+        let method = format!("{service}::{}", ident.unraw());
+        quote! {
+            #(#docs)*
+            pub fn #ident(&self, #(#names: #types),*) -> ::core::result::Result<#ret, C::Err> {
+                let req = #request::#variant { #(#names),* };
+                match ::rpcore::Call::call(&self.inner, req)? {
+                    #response::#variant(ret) => ::core::result::Result::Ok(ret),
+                    #[allow(unreachable_patterns)]
+                    _ => ::core::result::Result::Err(
+                        ::rpcore::UnexpectedResponse::new(#method).into(),
+                    ),
+                }
+            }
+        }
+    });
+
+    let request_doc = format!("The requests of the [`{service}`] service.");
+    let response_doc = format!("The responses of the [`{service}`] service.");
+    let handler_doc = format!(
+        "A handler that serves [`{request}`] by calling the matching method of a [`{service}`]."
+    );
+    let client_doc = format!("A client of the [`{service}`] service, that calls through `C`.");
+
+    Ok(quote! {
+        #item
+
+        #[doc = #request_doc]
+        #derive
+        #vis enum #request {
+            #(#request_variants,)*
+        }
+
+        #[doc = #response_doc]
+        #derive
+        #vis enum #response {
+            #(#response_variants,)*
+        }
+
+        #[doc = #handler_doc]
+        #[derive(Debug, Clone)]
+        #vis struct #handler<T> {
+            inner: T,
+        }
+
+        impl<T> #handler<T> {
+            pub fn new(inner: T) -> Self {
+                Self { inner }
+            }
+
+            pub fn get_ref(&self) -> &T {
+                &self.inner
+            }
+
+            pub fn get_mut(&mut self) -> &mut T {
+                &mut self.inner
+            }
+
+            pub fn into_inner(self) -> T {
+                self.inner
+            }
+        }
+
+        impl<T: #service> ::rpcore::Handler<#request> for #handler<T> {
+            type Ret = #response;
+
+            fn handle(
+                &mut self,
+                arg: #request,
+                callback: impl ::rpcore::Callback<Ret = Self::Ret>,
+            ) {
+                let ret = match arg {
+                    #(#dispatch_arms)*
+                };
+                ::rpcore::Callback::call(callback, ret);
+            }
+        }
+
+        #[doc = #client_doc]
+        #[derive(Debug, Clone)]
+        #vis struct #client<C> {
+            inner: C,
+        }
+
+        impl<C> #client<C> {
+            pub fn new(inner: C) -> Self {
+                Self { inner }
+            }
+
+            pub fn get_ref(&self) -> &C {
+                &self.inner
+            }
+
+            pub fn into_inner(self) -> C {
+                self.inner
+            }
+        }
+
+        impl<C> #client<C>
+        where
+            C: ::rpcore::Call<#request, Ret = #response>,
+            C::Err: ::core::convert::From<::rpcore::UnexpectedResponse>,
+        {
+            #(#client_methods)*
+        }
+    })
+}
@@ -0,0 +1,72 @@
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info, LevelFilter};
+use rpcore::balance::{Balance, TokenHash};
+use rpcore::server::{ShutdownBool, WithToken};
+use rpcore_core::server::Hooks;
+use rpcore_core::{Callback, Handler};
+use rpcore_mpsc::mpsc_server::{self, CallSettingToken};
+
+struct MyHooks;
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        info!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        error!("Server got error: {e}");
+    }
+}
+
+/// Sleeps for `arg` milliseconds, then returns the name of the backend.
+struct NamedHandler {
+    name: String,
+}
+
+impl Handler<WithToken<u64>> for NamedHandler {
+    type Ret = String;
+
+    fn handle(&mut self, arg: WithToken<u64>, callback: impl Callback<Ret = Self::Ret>) {
+        thread::sleep(Duration::from_millis(arg.data));
+        callback.call(self.name.clone());
+    }
+}
+
+fn main() {
+    simplelog::SimpleLogger::init(LevelFilter::Info, Default::default()).unwrap();
+
+    let backends = (0..3).map(|i| NamedHandler {
+        name: format!("backend {i}"),
+    });
+    // Calls from the same client always go to the same backend.
+    let handler = Balance::with_strategy(backends, TokenHash);
+    let load = handler.load();
+
+    let (mut server, client_builder) = mpsc_server::Builder::new().hooks(MyHooks).build(handler);
+
+    let clients: Vec<_> = (0..4)
+        .map(|i| {
+            let client = client_builder.build_client().unwrap();
+            thread::spawn(move || {
+                for _ in 0..3 {
+                    let ret = CallSettingToken::call(&client, WithToken::new(100)).unwrap();
+                    info!("client {i} was served by {ret}");
+                }
+            })
+        })
+        .collect();
+    drop(client_builder);
+
+    thread::spawn(move || loop {
+        info!("load: {load:?}");
+        thread::sleep(Duration::from_millis(150));
+    });
+
+    // The server shuts down once the clients are dropped.
+    server.serve(&ShutdownBool::new());
+    for client in clients {
+        client.join().unwrap();
+    }
+}
@@ -0,0 +1,69 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{error, info, LevelFilter};
+use rpcore::server::{Shutdown, ShutdownBool};
+use rpcore_core::server::Hooks;
+use rpcore_core::{Callback, Handler};
+use rpcore_mpsc::mpsc_server;
+
+struct MyHooks {
+    start: Instant,
+}
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        info!("Server shutdown after {:?}", self.start.elapsed());
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        error!("Server got error: {e}");
+    }
+
+    fn on_drained(&mut self, abandoned: usize) {
+        info!("Server drained, {abandoned} calls abandoned");
+    }
+}
+
+/// Calls back from another thread after `arg` milliseconds.
+struct AsyncHandler;
+
+impl Handler<u64> for AsyncHandler {
+    type Ret = u64;
+
+    fn handle(&mut self, arg: u64, callback: impl Callback<Ret = Self::Ret>) {
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(arg));
+            info!("call {arg} done");
+            callback.call(arg);
+        });
+    }
+}
+
+fn main() {
+    simplelog::SimpleLogger::init(LevelFilter::Info, Default::default()).unwrap();
+
+    let hooks = MyHooks {
+        start: Instant::now(),
+    };
+    let (mut server, client_builder) = mpsc_server::Builder::new().hooks(hooks).build(AsyncHandler);
+
+    for millis in [100, 300, 5000] {
+        let client = client_builder.build_client().unwrap();
+        thread::spawn(move || {
+            let ret = client.call(millis);
+            info!("call {millis} returned {ret:?}");
+        });
+    }
+
+    // Shuts down while the calls are still in flight. The server is waiting
+    // for more calls, and is woken up by the shutdown.
+    let shutdown = ShutdownBool::new();
+    let cloned = shutdown.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cloned.shutdown();
+    });
+
+    server.serve_graceful(&shutdown, Duration::from_secs(1));
+}
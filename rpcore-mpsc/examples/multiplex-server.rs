@@ -0,0 +1,97 @@
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info, LevelFilter};
+use rpcore::log::LogLayer;
+use rpcore::server::multiplex::{EpollReactor, Serve, Server};
+use rpcore::server::settings::{HasHooks, HasPolling};
+use rpcore::server::{Hooks, Shutdown, ShutdownBool};
+use rpcore_core::{Callback, Handler, HandlerBuilder};
+use rpcore_mpsc::{Invocation, RxWithEventFd, TxCallback, TxWithEventFd};
+
+struct MyHooks;
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        info!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        error!("Server got error: {e}");
+    }
+}
+
+struct MySettings {
+    polling: Option<Duration>,
+    hooks: MyHooks,
+}
+
+impl HasPolling for MySettings {
+    fn polling(&self) -> &Option<Duration> {
+        &self.polling
+    }
+}
+
+impl HasHooks for MySettings {
+    type H = MyHooks;
+
+    fn hooks(&mut self) -> &mut Self::H {
+        &mut self.hooks
+    }
+}
+
+struct MyHandler;
+
+impl Handler<String> for MyHandler {
+    type Ret = String;
+
+    fn handle(&mut self, arg: String, callback: impl Callback<Ret = Self::Ret>) {
+        callback.call(arg);
+    }
+}
+
+fn main() {
+    simplelog::SimpleLogger::init(LevelFilter::Info, Default::default()).unwrap();
+
+    let handler = HandlerBuilder::new()
+        .layer(LogLayer::default())
+        .handler(MyHandler);
+    let settings = MySettings {
+        polling: Some(Duration::from_micros(200)),
+        hooks: MyHooks,
+    };
+    let mut server = Server::new(EpollReactor::new(64).unwrap(), handler, settings);
+
+    for name in ["c1", "c2", "c3"] {
+        let (tx, rx) = RxWithEventFd::channel().unwrap();
+        server.add_source(rx).unwrap();
+        client_random_calling(name, tx);
+    }
+
+    let shutdown = ShutdownBool::new();
+    shutdown_after_3s(shutdown.clone());
+
+    server.serve(&shutdown);
+}
+
+fn client_random_calling(name: &'static str, tx: TxWithEventFd<String, String>) {
+    thread::spawn(move || loop {
+        let sleep_dur = Duration::from_millis(rand::random::<u64>() % 100);
+        thread::sleep(sleep_dur);
+
+        let (callback_tx, callback_rx) = oneshot::channel();
+        let inv = Invocation {
+            arg: format!("{name}: sleep {sleep_dur:?}"),
+            callback: TxCallback::new(callback_tx),
+        };
+        tx.send(inv).unwrap();
+        let _ = callback_rx.recv().unwrap();
+    });
+}
+
+fn shutdown_after_3s(shutdown: ShutdownBool) {
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(3));
+        shutdown.shutdown();
+    });
+}
@@ -0,0 +1,59 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{error, info, LevelFilter};
+use rpcore::server::ShutdownBool;
+use rpcore_core::server::Hooks;
+use rpcore_core::{Callback, Handler};
+use rpcore_mpsc::mpsc_server;
+
+#[derive(Clone)]
+struct MyHooks;
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        info!("Server thread shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        error!("Server thread got error: {e}");
+    }
+}
+
+/// Sleeps for `arg` milliseconds, then returns the name of the serving thread.
+struct SlowHandler;
+
+impl Handler<u64> for SlowHandler {
+    type Ret = String;
+
+    fn handle(&mut self, arg: u64, callback: impl Callback<Ret = Self::Ret>) {
+        thread::sleep(Duration::from_millis(arg));
+        callback.call(thread::current().name().unwrap_or_default().to_owned());
+    }
+}
+
+fn main() {
+    simplelog::SimpleLogger::init(LevelFilter::Info, Default::default()).unwrap();
+
+    let (mut server, client_builder) = mpsc_server::Builder::new()
+        .hooks(MyHooks)
+        .build_pool_with(4, || SlowHandler);
+
+    let start = Instant::now();
+    let callers: Vec<_> = (0..8)
+        .map(|i| {
+            let client = client_builder.build_client().unwrap();
+            thread::spawn(move || {
+                let ret = client.call(200).unwrap();
+                info!("call {i} served by {ret} after {:?}", start.elapsed());
+            })
+        })
+        .collect();
+    drop(client_builder);
+
+    // Every serving thread shuts down once the clients are dropped.
+    server.serve(&ShutdownBool::new());
+    for caller in callers {
+        caller.join().unwrap();
+    }
+}
@@ -0,0 +1,65 @@
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info, LevelFilter};
+use rpcore::rate_limit::RateLimitLayer;
+use rpcore::server::ShutdownBool;
+use rpcore_core::server::Hooks;
+use rpcore_core::{Callback, Handler, HandlerBuilder};
+use rpcore_mpsc::mpsc_server::{self, MpscClient};
+
+struct MyHooks;
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        info!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        error!("Server got error: {e}");
+    }
+}
+
+struct MyHandler;
+
+impl Handler<u32> for MyHandler {
+    type Ret = String;
+
+    fn handle(&mut self, arg: u32, callback: impl Callback<Ret = Self::Ret>) {
+        callback.call(format!("handled #{arg}"));
+    }
+}
+
+fn main() {
+    simplelog::SimpleLogger::init(LevelFilter::Info, Default::default()).unwrap();
+
+    // Lets through bursts of 3 calls, refilled at 10 calls per second.
+    let handler = HandlerBuilder::new()
+        .layer(
+            RateLimitLayer::new(10, Duration::from_secs(1))
+                .burst(3)
+                .shed(|| "overloaded".to_owned()),
+        )
+        .handler(MyHandler);
+
+    let (mut server, client_builder) = mpsc_server::Builder::new().hooks(MyHooks).build(handler);
+
+    let client = client_builder.build_client().unwrap();
+    drop(client_builder);
+    client_bursting(client);
+
+    // The server shuts down once the client is dropped.
+    server.serve(&ShutdownBool::new());
+}
+
+fn client_bursting(client: MpscClient<u32, String>) {
+    thread::spawn(move || {
+        for burst in 0..3 {
+            for i in 0..5 {
+                let ret = client.call(burst * 5 + i).unwrap();
+                info!("burst {burst}: {ret}");
+            }
+            thread::sleep(Duration::from_millis(150));
+        }
+    });
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{error, info, LevelFilter};
+use rpcore::retry::{Backoff, RetryLayer};
+use rpcore::server::ShutdownBool;
+use rpcore_core::server::Hooks;
+use rpcore_core::{Callback, Handler, HandlerBuilder};
+use rpcore_mpsc::mpsc_server;
+
+struct MyHooks;
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        info!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        error!("Server got error: {e}");
+    }
+}
+
+/// Fails the first `arg` attempts of every call with the same `arg`.
+///
+/// Retries are handled by a clone, so the attempts are shared.
+#[derive(Default, Clone)]
+struct FlakyHandler {
+    attempts: Arc<Mutex<HashMap<u32, u32>>>,
+}
+
+impl Handler<u32> for FlakyHandler {
+    type Ret = Result<String, String>;
+
+    fn handle(&mut self, arg: u32, callback: impl Callback<Ret = Self::Ret>) {
+        let attempt = {
+            let mut attempts = self.attempts.lock().unwrap();
+            let attempt = attempts.entry(arg).or_default();
+            *attempt += 1;
+            *attempt
+        };
+        if attempt <= arg {
+            info!("call {arg} failed attempt {attempt}");
+            callback.call(Err(format!("failed after {attempt} attempts")));
+        } else {
+            callback.call(Ok(format!("succeeded at attempt {attempt}")));
+        }
+    }
+}
+
+fn main() {
+    simplelog::SimpleLogger::init(LevelFilter::Info, Default::default()).unwrap();
+
+    let handler = HandlerBuilder::new()
+        .layer(
+            RetryLayer::new(|ret: &Result<String, String>| ret.is_err())
+                .max_attempts(3)
+                .backoff(Backoff::Fixed(Duration::from_millis(20))),
+        )
+        .handler(FlakyHandler::default());
+
+    let (mut server, client_builder) = mpsc_server::Builder::new().hooks(MyHooks).build(handler);
+
+    let client = client_builder.build_client().unwrap();
+    drop(client_builder);
+    std::thread::spawn(move || {
+        for arg in 0..4 {
+            let ret = client.call(arg).unwrap();
+            info!("call {arg} returned: {ret:?}");
+        }
+    });
+
+    // The server shuts down once the client is dropped.
+    server.serve(&ShutdownBool::new());
+}
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use log::{error, info, LevelFilter};
+use rpcore::server::ShutdownBool;
+use rpcore_core::server::Hooks;
+use rpcore_mpsc::mpsc_server;
+
+struct MyHooks;
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        info!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        error!("Server got error: {e}");
+    }
+}
+
+#[rpcore::service(derive(Debug))]
+trait KeyValue {
+    /// Sets `key` to `value`, returning the previous value.
+    fn set(&mut self, key: String, value: u64) -> Option<u64>;
+
+    fn get(&self, key: String) -> Option<u64>;
+
+    fn clear(&mut self);
+}
+
+#[derive(Default)]
+struct Store {
+    map: HashMap<String, u64>,
+}
+
+impl KeyValue for Store {
+    fn set(&mut self, key: String, value: u64) -> Option<u64> {
+        self.map.insert(key, value)
+    }
+
+    fn get(&self, key: String) -> Option<u64> {
+        self.map.get(&key).copied()
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+fn main() {
+    simplelog::SimpleLogger::init(LevelFilter::Info, Default::default()).unwrap();
+
+    let handler = KeyValueHandler::new(Store::default());
+    let (mut server, client_builder) = mpsc_server::Builder::new().hooks(MyHooks).build(handler);
+
+    let client = KeyValueClient::new(client_builder.build_client().unwrap());
+    drop(client_builder);
+    std::thread::spawn(move || {
+        info!("set a: {:?}", client.set("a".to_owned(), 1).unwrap());
+        info!("set a: {:?}", client.set("a".to_owned(), 2).unwrap());
+        info!("get a: {:?}", client.get("a".to_owned()).unwrap());
+        client.clear().unwrap();
+        info!(
+            "get a after clear: {:?}",
+            client.get("a".to_owned()).unwrap()
+        );
+    });
+
+    // The server shuts down once the client is dropped.
+    server.serve(&ShutdownBool::new());
+}
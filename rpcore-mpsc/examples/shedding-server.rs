@@ -0,0 +1,75 @@
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info, LevelFilter};
+use rpcore::concurrency_limit::ConcurrencyLimitLayer;
+use rpcore::server::ShutdownBool;
+use rpcore_core::server::Hooks;
+use rpcore_core::{Callback, Handler, HandlerBuilder};
+use rpcore_mpsc::mpsc_server::{self, MpscClient};
+
+struct MyHooks;
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        info!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        error!("Server got error: {e}");
+    }
+}
+
+/// Replies after 100ms on another thread.
+struct SlowHandler;
+
+impl Handler<u32> for SlowHandler {
+    type Ret = String;
+
+    fn handle(&mut self, arg: u32, callback: impl Callback<Ret = Self::Ret>) {
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            callback.call(format!("handled #{arg}"));
+        });
+    }
+}
+
+fn main() {
+    simplelog::SimpleLogger::init(LevelFilter::Info, Default::default()).unwrap();
+
+    // Handles 2 calls at a time, queues 2 more and sheds the rest, without
+    // ever blocking the serve thread.
+    let handler = HandlerBuilder::new()
+        .layer(ConcurrencyLimitLayer::new(2).queue(2, || "overloaded".to_owned()))
+        .handler(SlowHandler);
+    let load = handler.load();
+
+    let (mut server, client_builder) = mpsc_server::Builder::new().hooks(MyHooks).build(handler);
+
+    let callers: Vec<_> = (0..6)
+        .map(|i| {
+            let client = client_builder.build_client().unwrap();
+            thread::sleep(Duration::from_millis(10));
+            client_calling(client, i)
+        })
+        .collect();
+    drop(client_builder);
+
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        info!("load: {load:?}");
+        for caller in callers {
+            caller.join().unwrap();
+        }
+    });
+
+    // The server shuts down once all clients are dropped.
+    server.serve(&ShutdownBool::new());
+}
+
+fn client_calling(client: MpscClient<u32, String>, i: u32) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let ret = client.call(i).unwrap();
+        info!("call #{i} returned: {ret}");
+    })
+}
@@ -0,0 +1,64 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{error, info, LevelFilter};
+use rpcore::server::ShutdownBool;
+use rpcore::thread_pool::ThreadPoolLayer;
+use rpcore_core::server::Hooks;
+use rpcore_core::{Callback, Handler, HandlerBuilder};
+use rpcore_mpsc::mpsc_server;
+
+struct MyHooks;
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        info!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        error!("Server got error: {e}");
+    }
+}
+
+/// Sleeps for `arg` milliseconds before returning it.
+#[derive(Clone)]
+struct SlowHandler;
+
+impl Handler<u64> for SlowHandler {
+    type Ret = Option<u64>;
+
+    fn handle(&mut self, arg: u64, callback: impl Callback<Ret = Self::Ret>) {
+        thread::sleep(Duration::from_millis(arg));
+        callback.call(Some(arg));
+    }
+}
+
+fn main() {
+    simplelog::SimpleLogger::init(LevelFilter::Info, Default::default()).unwrap();
+
+    let handler = HandlerBuilder::new()
+        .layer(ThreadPoolLayer::new(4).queue_size(2).shed(|| None))
+        .handler(SlowHandler);
+
+    let (mut server, client_builder) = mpsc_server::Builder::new().hooks(MyHooks).build(handler);
+
+    let start = Instant::now();
+    // 4 calls are handled at once and 2 are queued, the rest are shed.
+    let callers: Vec<_> = (0..8)
+        .map(|i| {
+            let client = client_builder.build_client().unwrap();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(10 * i));
+                let ret = client.call(200).unwrap();
+                info!("call {i} returned {ret:?} after {:?}", start.elapsed());
+            })
+        })
+        .collect();
+    drop(client_builder);
+
+    // The server shuts down once the clients are dropped.
+    server.serve(&ShutdownBool::new());
+    for caller in callers {
+        caller.join().unwrap();
+    }
+}
@@ -0,0 +1,63 @@
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info, LevelFilter};
+use rpcore::server::ShutdownBool;
+use rpcore::timeout::TimeoutLayer;
+use rpcore_core::server::Hooks;
+use rpcore_core::{Callback, Handler, HandlerBuilder};
+use rpcore_mpsc::mpsc_server::{self, MpscClient};
+
+struct MyHooks;
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        info!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        error!("Server got error: {e}");
+    }
+}
+
+/// Replies after sleeping for the requested duration on another thread.
+struct SleepHandler;
+
+impl Handler<Duration> for SleepHandler {
+    type Ret = String;
+
+    fn handle(&mut self, arg: Duration, callback: impl Callback<Ret = Self::Ret>) {
+        thread::spawn(move || {
+            thread::sleep(arg);
+            callback.call(format!("slept {arg:?}"));
+        });
+    }
+}
+
+fn main() {
+    simplelog::SimpleLogger::init(LevelFilter::Info, Default::default()).unwrap();
+
+    let handler = HandlerBuilder::new()
+        .layer(TimeoutLayer::new(Duration::from_millis(50), || {
+            "timed out".to_owned()
+        }))
+        .handler(SleepHandler);
+
+    let (mut server, client_builder) = mpsc_server::Builder::new().hooks(MyHooks).build(handler);
+
+    let client = client_builder.build_client().unwrap();
+    drop(client_builder);
+    client_calling(client);
+
+    // The server shuts down once the client is dropped.
+    server.serve(&ShutdownBool::new());
+}
+
+fn client_calling(client: MpscClient<Duration, String>) {
+    thread::spawn(move || {
+        for millis in [10, 100, 30, 200] {
+            let ret = client.call(Duration::from_millis(millis)).unwrap();
+            info!("call {millis}ms returned: {ret}");
+        }
+    });
+}
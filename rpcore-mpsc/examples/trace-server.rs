@@ -0,0 +1,69 @@
+use std::thread;
+use std::time::Duration;
+
+use rpcore::server::{ShutdownBool, WithToken};
+use rpcore::trace::TraceLayer;
+use rpcore_core::server::Hooks;
+use rpcore_core::{Callback, Handler, HandlerBuilder};
+use rpcore_mpsc::mpsc_server::{self, CallSettingToken, MpscClient};
+use tracing::Level;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+struct MyHooks;
+
+impl Hooks for MyHooks {
+    fn on_shutdown(&mut self) {
+        tracing::info!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        tracing::error!("Server got error: {e}");
+    }
+}
+
+struct MyHandler;
+
+impl Handler<WithToken<String>> for MyHandler {
+    type Ret = String;
+
+    fn handle(&mut self, arg: WithToken<String>, callback: impl Callback<Ret = Self::Ret>) {
+        tracing::debug!("handling {} bytes", arg.data.len());
+        callback.call(arg.data.to_uppercase());
+    }
+}
+
+fn main() {
+    // Spans are printed when they close, along with their fields.
+    tracing_subscriber::fmt()
+        .with_max_level(Level::DEBUG)
+        .with_span_events(FmtSpan::CLOSE)
+        .init();
+
+    let handler = HandlerBuilder::new()
+        .layer(
+            TraceLayer::new()
+                .handler_name("upper")
+                .redact_arg()
+                .with_token(),
+        )
+        .handler(MyHandler);
+
+    let (mut server, client_builder) = mpsc_server::Builder::new().hooks(MyHooks).build(handler);
+
+    for _ in 0..2 {
+        client_calling(client_builder.build_client().unwrap());
+    }
+    drop(client_builder);
+
+    // The server shuts down once all clients are dropped.
+    server.serve(&ShutdownBool::new());
+}
+
+fn client_calling(client: MpscClient<WithToken<String>, String>) {
+    thread::spawn(move || {
+        for secret in ["hunter2", "swordfish"] {
+            CallSettingToken::call(&client, WithToken::new(secret.to_owned())).unwrap();
+            thread::sleep(Duration::from_millis(10));
+        }
+    });
+}
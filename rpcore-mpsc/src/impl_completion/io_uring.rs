@@ -22,26 +22,26 @@ impl<'a, Arg, Ret> completion::Proactor<Submitter<'a>, SubmissionQueue<'a>, cque
     }
 
     fn submit(&mut self, sq: &mut SubmissionQueue<'a>, token: u64) -> Result<(), Self::SubmitErr> {
-        const BUF_LEN: usize = 8;
-        static DONT_CARE: [u8; BUF_LEN] = [0; BUF_LEN];
-
+        // Poll the eventfd instead of reading it: the eventfd is non-blocking,
+        // so a read would complete with EAGAIN instead of waiting. The counter
+        // is reset in `on_complete`.
         let efd = Fd(self.eventfd.as_raw_fd());
-        let read_entry = opcode::Read::new(efd, &DONT_CARE as *const u8 as *mut u8, BUF_LEN as u32)
+        let poll_entry = opcode::PollAdd::new(efd, libc::POLLIN as u32)
             .build()
             .user_data(token);
 
-        // SAFETY: DONT_CARE is a statically valid buffer whose value we do not care about.
-        // Although eventfd is not always valid, even if it is closed during io_uring
-        // processing, it will only result in a negative return value (error) in the CQE,
-        // and will not cause any memory safety issues.
-        unsafe { sq.push(&read_entry) }
+        // SAFETY: No buffer is involved. Although eventfd is not always valid,
+        // even if it is closed during io_uring processing, it will only result
+        // in a negative return value (error) in the CQE, and will not cause any
+        // memory safety issues.
+        unsafe { sq.push(&poll_entry) }
     }
 
     fn on_complete(&mut self, entry: cqueue::Entry) -> Result<(), Self::OnCompleteErr> {
         if entry.result() < 0 {
-            Err(io::Error::from_raw_os_error(entry.result()))
+            Err(io::Error::from_raw_os_error(-entry.result()))
         } else {
-            Ok(())
+            self.clear_eventfd()
         }
     }
 }
@@ -25,4 +25,8 @@ impl<Arg, Ret> readiness::EventSource<mio::Registry> for RxWithEventFd<Arg, Ret>
     fn deregister(&mut self, registry: &mut mio::Registry) -> Result<(), Self::Err> {
         registry.deregister(&mut SourceFd(&self.eventfd.as_raw_fd()))
     }
+
+    fn on_ready(&mut self) -> Result<(), Self::Err> {
+        self.clear_eventfd()
+    }
 }
@@ -1,3 +1,4 @@
+use nix::errno::Errno;
 use nix::sys::epoll::{Epoll, EpollEvent, EpollFlags};
 use rpcore_core::invocation_source::readiness;
 
@@ -16,4 +17,9 @@ impl<Arg, Ret> readiness::EventSource<Epoll> for RxWithEventFd<Arg, Ret> {
     fn deregister(&mut self, registry: &mut Epoll) -> Result<(), Self::Err> {
         registry.delete(&self.eventfd)
     }
+
+    fn on_ready(&mut self) -> Result<(), Self::Err> {
+        self.clear_eventfd()
+            .map_err(|e| Errno::from_raw(e.raw_os_error().unwrap_or(0)))
+    }
 }
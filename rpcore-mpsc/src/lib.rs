@@ -6,6 +6,11 @@ mod rx_with_event_fd;
 #[cfg(any(target_os = "linux", target_os = "android"))]
 pub use rx_with_event_fd::RxWithEventFd;
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod tx_with_event_fd;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub use tx_with_event_fd::TxWithEventFd;
+
 mod tx_callback;
 pub use tx_callback::TxCallback;
 
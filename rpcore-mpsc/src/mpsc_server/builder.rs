@@ -1,12 +1,15 @@
-use std::sync::mpsc;
+use std::sync::Arc;
 use std::time::Duration;
 
+use crossbeam_channel::Sender;
 use rpcore_core::server::singleplex::Server;
 use rpcore_core::server::SyncTokenAllocator;
 use rpcore_core::Handler;
 
-use crate::mpsc_server::{Error, MpscClient, MpscServer, MpscSyncClient, Result, Settings};
-use crate::{Invocation, Rx};
+use crate::mpsc_server::channel::{Message, ServerRx, Waker};
+use crate::mpsc_server::{
+    Error, MpscClient, MpscServer, MpscServerPool, MpscSyncClient, Result, Settings,
+};
 
 pub struct Builder<B, Hooks = ()> {
     settings: Settings<Hooks>,
@@ -70,18 +73,62 @@ impl<Hooks> Builder<Unbounded, Hooks> {
     where
         H: Handler<Arg>,
     {
-        let (tx, rx) = mpsc::channel();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let tx = Arc::new(tx);
         let inner = Server {
-            inv_src: Rx::new(rx),
+            inv_src: ServerRx { rx },
             handler,
             settings: self.settings,
         };
+        let waker = Waker {
+            tx: Arc::downgrade(&tx),
+        };
+        let client_builder = ClientBuilder {
+            token_allocator: SyncTokenAllocator::default(),
+            tx,
+        };
+
+        (MpscServer { inner, waker }, client_builder)
+    }
+
+    /// Builds a server that serves on `threads` threads, each with its own
+    /// clone of `handler`.
+    pub fn build_pool<H, Arg>(
+        self,
+        threads: usize,
+        handler: H,
+    ) -> (MpscServerPool<H, Arg, Hooks>, ClientBuilder<Arg, H::Ret>)
+    where
+        H: Handler<Arg> + Clone,
+        Hooks: Clone,
+    {
+        self.build_pool_with(threads, || handler.clone())
+    }
+
+    /// Builds a server that serves on `threads` threads, each with its own
+    /// handler made by `make_handler`.
+    pub fn build_pool_with<H, Arg>(
+        self,
+        threads: usize,
+        make_handler: impl FnMut() -> H,
+    ) -> (MpscServerPool<H, Arg, Hooks>, ClientBuilder<Arg, H::Ret>)
+    where
+        H: Handler<Arg>,
+        Hooks: Clone,
+    {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let tx = Arc::new(tx);
+        let waker = Waker {
+            tx: Arc::downgrade(&tx),
+        };
         let client_builder = ClientBuilder {
             token_allocator: SyncTokenAllocator::default(),
             tx,
         };
 
-        (MpscServer { inner }, client_builder)
+        let server =
+            MpscServerPool::new(ServerRx { rx }, waker, threads, self.settings, make_handler);
+        (server, client_builder)
     }
 }
 
@@ -93,45 +140,95 @@ impl<Hooks> Builder<Bounded, Hooks> {
     where
         H: Handler<Arg>,
     {
-        let (tx, rx) = mpsc::sync_channel(self.bound.cap);
+        let (tx, rx) = crossbeam_channel::bounded(self.bound.cap);
+        let tx = Arc::new(tx);
         let inner = Server {
-            inv_src: Rx::new(rx),
+            inv_src: ServerRx { rx },
             handler,
             settings: self.settings,
         };
+        let waker = Waker {
+            tx: Arc::downgrade(&tx),
+        };
+        let client_builder = SyncClientBuilder {
+            token_allocator: SyncTokenAllocator::default(),
+            tx,
+        };
+
+        (MpscServer { inner, waker }, client_builder)
+    }
+
+    /// Builds a server that serves on `threads` threads, each with its own
+    /// clone of `handler`.
+    pub fn build_pool<H, Arg>(
+        self,
+        threads: usize,
+        handler: H,
+    ) -> (
+        MpscServerPool<H, Arg, Hooks>,
+        SyncClientBuilder<Arg, H::Ret>,
+    )
+    where
+        H: Handler<Arg> + Clone,
+        Hooks: Clone,
+    {
+        self.build_pool_with(threads, || handler.clone())
+    }
+
+    /// Builds a server that serves on `threads` threads, each with its own
+    /// handler made by `make_handler`.
+    pub fn build_pool_with<H, Arg>(
+        self,
+        threads: usize,
+        make_handler: impl FnMut() -> H,
+    ) -> (
+        MpscServerPool<H, Arg, Hooks>,
+        SyncClientBuilder<Arg, H::Ret>,
+    )
+    where
+        H: Handler<Arg>,
+        Hooks: Clone,
+    {
+        let (tx, rx) = crossbeam_channel::bounded(self.bound.cap);
+        let tx = Arc::new(tx);
+        let waker = Waker {
+            tx: Arc::downgrade(&tx),
+        };
         let client_builder = SyncClientBuilder {
             token_allocator: SyncTokenAllocator::default(),
             tx,
         };
 
-        (MpscServer { inner }, client_builder)
+        let server =
+            MpscServerPool::new(ServerRx { rx }, waker, threads, self.settings, make_handler);
+        (server, client_builder)
     }
 }
 
 pub struct ClientBuilder<Arg, Ret> {
     token_allocator: SyncTokenAllocator,
-    tx: mpsc::Sender<Invocation<Arg, Ret>>,
+    tx: Arc<Sender<Message<Arg, Ret>>>,
 }
 
 impl<Arg, Ret> ClientBuilder<Arg, Ret> {
     pub fn build_client(&self) -> Result<MpscClient<Arg, Ret>> {
         Ok(MpscClient {
             token: self.token_allocator.alloc().ok_or(Error::TooManyClients)?,
-            tx: self.tx.clone(),
+            tx: Arc::clone(&self.tx),
         })
     }
 }
 
 pub struct SyncClientBuilder<Arg, Ret> {
     token_allocator: SyncTokenAllocator,
-    tx: mpsc::SyncSender<Invocation<Arg, Ret>>,
+    tx: Arc<Sender<Message<Arg, Ret>>>,
 }
 
 impl<Arg, Ret> SyncClientBuilder<Arg, Ret> {
     pub fn build_client(&self) -> Result<MpscSyncClient<Arg, Ret>> {
         Ok(MpscSyncClient {
             token: self.token_allocator.alloc().ok_or(Error::TooManyClients)?,
-            tx: self.tx.clone(),
+            tx: Arc::clone(&self.tx),
         })
     }
 }
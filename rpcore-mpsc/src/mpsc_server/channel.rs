@@ -0,0 +1,134 @@
+use std::sync::Weak;
+
+use crossbeam_channel::{Receiver, Sender};
+use rpcore_core::invocation_source::recv::{self, RecvInvocation, TryRecvInvocation};
+
+use crate::{Invocation, TxCallback};
+
+/// A message sent to a [`MpscServer`](super::MpscServer).
+pub(crate) enum Message<Arg, Ret> {
+    Invocation(Invocation<Arg, Ret>),
+    /// Wakes up the server blocked in `recv`, to check for shutdown.
+    Wake,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum RecvError {
+    #[error("receiving on a closed channel")]
+    Closed,
+
+    #[error("receiving interrupted")]
+    Interrupted,
+}
+
+impl recv::Error for RecvError {
+    fn is_closed(&self) -> bool {
+        matches!(self, Self::Closed)
+    }
+
+    fn is_empty(&self) -> bool {
+        false
+    }
+
+    fn is_interrupted(&self) -> bool {
+        matches!(self, Self::Interrupted)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum TryRecvError {
+    #[error("receiving on an empty channel")]
+    Empty,
+
+    #[error("receiving on a closed channel")]
+    Closed,
+
+    #[error("receiving interrupted")]
+    Interrupted,
+}
+
+impl recv::Error for TryRecvError {
+    fn is_closed(&self) -> bool {
+        matches!(self, Self::Closed)
+    }
+
+    fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    fn is_interrupted(&self) -> bool {
+        matches!(self, Self::Interrupted)
+    }
+}
+
+/// The receiving end of the channel of a server.
+///
+/// The channel is MPMC, so the threads of a pool each receive from their own
+/// clone, and every invocation is received by exactly one of them.
+pub(crate) struct ServerRx<Arg, Ret> {
+    pub(crate) rx: Receiver<Message<Arg, Ret>>,
+}
+
+impl<Arg, Ret> Clone for ServerRx<Arg, Ret> {
+    fn clone(&self) -> Self {
+        Self {
+            rx: self.rx.clone(),
+        }
+    }
+}
+
+impl<Arg, Ret> RecvInvocation<Arg, TxCallback<Ret>> for ServerRx<Arg, Ret>
+where
+    Ret: Send + 'static,
+{
+    type RecvErr = RecvError;
+
+    fn recv(&mut self) -> Result<Invocation<Arg, Ret>, Self::RecvErr> {
+        match self.rx.recv() {
+            Ok(Message::Invocation(inv)) => Ok(inv),
+            Ok(Message::Wake) => Err(RecvError::Interrupted),
+            Err(crossbeam_channel::RecvError) => Err(RecvError::Closed),
+        }
+    }
+}
+
+impl<Arg, Ret> TryRecvInvocation<Arg, TxCallback<Ret>> for ServerRx<Arg, Ret>
+where
+    Ret: Send + 'static,
+{
+    type TryRecvErr = TryRecvError;
+
+    fn try_recv(&mut self) -> Result<Invocation<Arg, Ret>, Self::TryRecvErr> {
+        match self.rx.try_recv() {
+            Ok(Message::Invocation(inv)) => Ok(inv),
+            Ok(Message::Wake) => Err(TryRecvError::Interrupted),
+            Err(crossbeam_channel::TryRecvError::Empty) => Err(TryRecvError::Empty),
+            Err(crossbeam_channel::TryRecvError::Disconnected) => Err(TryRecvError::Closed),
+        }
+    }
+}
+
+/// Sends [`Message::Wake`] to a server.
+///
+/// It only holds a weak reference to the sender of the clients, so that the
+/// channel is still closed once all clients are dropped.
+pub(crate) struct Waker<Arg, Ret> {
+    pub(crate) tx: Weak<Sender<Message<Arg, Ret>>>,
+}
+
+impl<Arg, Ret> Waker<Arg, Ret> {
+    pub(crate) fn wake(&self) {
+        // A full channel wakes up the server anyway.
+        if let Some(tx) = self.tx.upgrade() {
+            let _ = tx.try_send(Message::Wake);
+        }
+    }
+}
+
+impl<Arg, Ret> Clone for Waker<Arg, Ret> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: Weak::clone(&self.tx),
+        }
+    }
+}
@@ -1,21 +1,24 @@
 use std::future::Future;
-use std::sync::mpsc;
+use std::sync::Arc;
 use std::time::Duration;
 
+use crossbeam_channel::Sender;
 use oneshot::RecvTimeoutError;
 use rpcore_core::server::{SetToken, Token};
+use rpcore_core::Call;
 
+use crate::mpsc_server::channel::Message;
 use crate::mpsc_server::{Error, Result};
 use crate::{Invocation, TxCallback};
 
 pub struct MpscClient<Arg, Ret> {
     pub(crate) token: Token,
-    pub(crate) tx: mpsc::Sender<Invocation<Arg, Ret>>,
+    pub(crate) tx: Arc<Sender<Message<Arg, Ret>>>,
 }
 
 pub struct MpscSyncClient<Arg, Ret> {
     pub(crate) token: Token,
-    pub(crate) tx: mpsc::SyncSender<Invocation<Arg, Ret>>,
+    pub(crate) tx: Arc<Sender<Message<Arg, Ret>>>,
 }
 
 pub trait CallSettingToken {
@@ -44,7 +47,9 @@ macro_rules! impl_call_setting_token {
                     arg,
                     callback: TxCallback::new(tx),
                 };
-                self.tx.send(inv).map_err(|_| Error::ServerClosed)?;
+                self.tx
+                    .send(Message::Invocation(inv))
+                    .map_err(|_| Error::ServerClosed)?;
                 rx.recv().map_err(|_| Error::ServerInternalError)
             }
 
@@ -56,7 +61,9 @@ macro_rules! impl_call_setting_token {
                     arg,
                     callback: TxCallback::new(tx),
                 };
-                self.tx.send(inv).map_err(|_| Error::ServerClosed)?;
+                self.tx
+                    .send(Message::Invocation(inv))
+                    .map_err(|_| Error::ServerClosed)?;
                 match rx.recv_timeout(timeout) {
                     Ok(ret) => Ok(ret),
                     Err(RecvTimeoutError::Timeout) => Err(Error::ServerTimeout),
@@ -72,7 +79,9 @@ macro_rules! impl_call_setting_token {
                     arg,
                     callback: TxCallback::new(tx),
                 };
-                self.tx.send(inv).map_err(|_| Error::ServerClosed)?;
+                self.tx
+                    .send(Message::Invocation(inv))
+                    .map_err(|_| Error::ServerClosed)?;
                 rx.await.map_err(|_| Error::ServerInternalError)
             }
         }
@@ -88,7 +97,9 @@ macro_rules! impl_call {
                     arg,
                     callback: TxCallback::new(tx),
                 };
-                self.tx.send(inv).map_err(|_| Error::ServerClosed)?;
+                self.tx
+                    .send(Message::Invocation(inv))
+                    .map_err(|_| Error::ServerClosed)?;
                 rx.recv().map_err(|_| Error::ServerInternalError)
             }
 
@@ -98,7 +109,9 @@ macro_rules! impl_call {
                     arg,
                     callback: TxCallback::new(tx),
                 };
-                self.tx.send(inv).map_err(|_| Error::ServerClosed)?;
+                self.tx
+                    .send(Message::Invocation(inv))
+                    .map_err(|_| Error::ServerClosed)?;
                 match rx.recv_timeout(timeout) {
                     Ok(ret) => Ok(ret),
                     Err(RecvTimeoutError::Timeout) => Err(Error::ServerTimeout),
@@ -112,10 +125,21 @@ macro_rules! impl_call {
                     arg,
                     callback: TxCallback::new(tx),
                 };
-                self.tx.send(inv).map_err(|_| Error::ServerClosed)?;
+                self.tx
+                    .send(Message::Invocation(inv))
+                    .map_err(|_| Error::ServerClosed)?;
                 rx.await.map_err(|_| Error::ServerInternalError)
             }
         }
+
+        impl<Arg, Ret> Call<Arg> for $client<Arg, Ret> {
+            type Ret = Ret;
+            type Err = Error;
+
+            fn call(&self, arg: Arg) -> Result<Ret> {
+                $client::call(self, arg)
+            }
+        }
     };
 }
 
@@ -1,12 +1,17 @@
 pub mod builder;
 pub use builder::Builder;
 
+mod channel;
+
 mod settings;
 pub(crate) use settings::Settings;
 
 mod server;
 pub use server::MpscServer;
 
+mod pool;
+pub use pool::MpscServerPool;
+
 mod client;
 pub use client::{CallSettingToken, MpscClient, MpscSyncClient};
 
@@ -23,6 +28,9 @@ pub enum Error {
 
     #[error("Server timeout")]
     ServerTimeout,
+
+    #[error(transparent)]
+    UnexpectedResponse(#[from] rpcore_core::UnexpectedResponse),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,119 @@
+use std::any::type_name;
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+
+use rpcore_core::server::singleplex::{ServeGraceful, ServeWithPolling, Server};
+use rpcore_core::server::WakeOnShutdown;
+use rpcore_core::Handler;
+
+use crate::mpsc_server::channel::{ServerRx, Waker};
+use crate::mpsc_server::Settings;
+
+type PoolServer<H, Arg, Ret, Hooks> = Server<ServerRx<Arg, Ret>, H, Settings<Hooks>>;
+
+/// A server that serves one channel on several threads, each with its own
+/// handler and hooks.
+pub struct MpscServerPool<H, Arg, Hooks>
+where
+    H: Handler<Arg>,
+{
+    pub(crate) servers: Vec<PoolServer<H, Arg, H::Ret, Hooks>>,
+    pub(crate) waker: Waker<Arg, H::Ret>,
+}
+
+impl<H, Arg, Hooks> MpscServerPool<H, Arg, Hooks>
+where
+    H: Handler<Arg>,
+{
+    pub(crate) fn new(
+        rx: ServerRx<Arg, H::Ret>,
+        waker: Waker<Arg, H::Ret>,
+        threads: usize,
+        settings: Settings<Hooks>,
+        mut make_handler: impl FnMut() -> H,
+    ) -> Self
+    where
+        Hooks: Clone,
+    {
+        assert!(threads > 0, "Threads must be greater than 0.");
+
+        let servers = (0..threads)
+            .map(|_| Server {
+                inv_src: rx.clone(),
+                handler: make_handler(),
+                settings: settings.clone(),
+            })
+            .collect();
+        Self { servers, waker }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.servers.len()
+    }
+}
+
+impl<H, Arg, Hooks> MpscServerPool<H, Arg, Hooks>
+where
+    H: Handler<Arg> + Send,
+    H::Ret: Send + 'static,
+    Arg: Send + 'static,
+    Hooks: rpcore_core::server::Hooks + Send,
+{
+    /// Serves on one thread per handler until all of them shut down, either
+    /// because of `shutdown`, which wakes up the threads waiting for calls, or
+    /// because all clients are dropped.
+    pub fn serve(&mut self, shutdown: &(impl WakeOnShutdown + Sync)) {
+        self.spawn_servers(shutdown, |server| {
+            ServeWithPolling::serve(server, shutdown);
+        });
+    }
+
+    /// Serves until `shutdown`, then every thread handles the queued calls and
+    /// waits up to `grace` for the calls it has in flight to return.
+    pub fn serve_graceful(&mut self, shutdown: &(impl WakeOnShutdown + Sync), grace: Duration) {
+        self.spawn_servers(shutdown, |server| {
+            ServeGraceful::serve_graceful(server, shutdown, grace);
+        });
+    }
+
+    /// Runs `serve` with every server on its own thread, each woken up by
+    /// `shutdown`, and returns once all of them return.
+    fn spawn_servers(
+        &mut self,
+        shutdown: &(impl WakeOnShutdown + Sync),
+        serve: impl Fn(&mut PoolServer<H, Arg, H::Ret, Hooks>) + Sync,
+    ) {
+        let serve = &serve;
+        thread::scope(|scope| {
+            for (i, server) in self.servers.iter_mut().enumerate() {
+                let waker = self.waker.clone();
+                thread::Builder::new()
+                    .name(format!("rpcore-mpsc-{i}"))
+                    .spawn_scoped(scope, move || {
+                        let _guard = shutdown.wake_on_shutdown({
+                            let waker = waker.clone();
+                            move || waker.wake()
+                        });
+                        serve(server);
+                        // A thread that shut down may have taken the wake-up
+                        // of another, or lost it to a full channel, so every
+                        // thread passes one on to the next on its way out.
+                        waker.wake();
+                    })
+                    .expect("Failed to spawn serving thread.");
+            }
+        });
+    }
+}
+
+impl<H, Arg, Hooks> fmt::Debug for MpscServerPool<H, Arg, Hooks>
+where
+    H: Handler<Arg>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(&format!("MpscServerPool<{}>", type_name::<H>()))
+            .field("threads", &self.threads())
+            .finish_non_exhaustive()
+    }
+}
@@ -1,24 +1,40 @@
-use rpcore_core::server::singleplex::{ServeWithPolling, Server};
-use rpcore_core::server::IsShuttingDown;
+use std::time::Duration;
+
+use rpcore_core::server::singleplex::{ServeGraceful, ServeWithPolling, Server};
+use rpcore_core::server::WakeOnShutdown;
 use rpcore_core::Handler;
 
+use crate::mpsc_server::channel::{ServerRx, Waker};
 use crate::mpsc_server::Settings;
-use crate::Rx;
 
 pub struct MpscServer<H, Arg, Hooks>
 where
     H: Handler<Arg>,
 {
-    pub(crate) inner: Server<Rx<Arg, H::Ret>, H, Settings<Hooks>>,
+    pub(crate) inner: Server<ServerRx<Arg, H::Ret>, H, Settings<Hooks>>,
+    pub(crate) waker: Waker<Arg, H::Ret>,
 }
 
 impl<H, Arg, Hooks> MpscServer<H, Arg, Hooks>
 where
     H: Handler<Arg>,
     H::Ret: Send + 'static,
+    Arg: Send + 'static,
     Hooks: rpcore_core::server::Hooks,
 {
-    pub fn serve(&mut self, shutdown: &impl IsShuttingDown) {
+    /// Serves until `shutdown`, which wakes up the server if it is waiting for
+    /// calls, or until all clients are dropped.
+    pub fn serve(&mut self, shutdown: &impl WakeOnShutdown) {
+        let waker = self.waker.clone();
+        let _guard = shutdown.wake_on_shutdown(move || waker.wake());
         ServeWithPolling::serve(&mut self.inner, shutdown);
     }
+
+    /// Serves until `shutdown`, then handles the queued calls and waits up to
+    /// `grace` for the calls in flight to return.
+    pub fn serve_graceful(&mut self, shutdown: &impl WakeOnShutdown, grace: Duration) {
+        let waker = self.waker.clone();
+        let _guard = shutdown.wake_on_shutdown(move || waker.wake());
+        ServeGraceful::serve_graceful(&mut self.inner, shutdown, grace);
+    }
 }
@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use rpcore_core::server::settings::{HasHooks, HasPolling};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct Settings<Hooks = ()> {
     pub(crate) polling: Option<Duration>,
     pub(crate) hooks: Hooks,
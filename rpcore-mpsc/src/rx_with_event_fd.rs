@@ -1,11 +1,11 @@
 //! Provides a receiver with an associated event file descriptor (eventfd) for
 //! asynchronous notifications.
 
-use std::os::fd::{FromRawFd, OwnedFd};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
 use std::sync::mpsc;
 use std::{io, ops};
 
-use crate::{Invocation, Rx};
+use crate::{Invocation, Rx, TxWithEventFd};
 
 /// A receiver that wraps an `Rx` with an associated event file descriptor.
 pub struct RxWithEventFd<Arg, Ret> {
@@ -38,6 +38,34 @@ impl<Arg, Ret> RxWithEventFd<Arg, Ret> {
         }
     }
 
+    /// Creates a new channel whose sender signals the event file descriptor of
+    /// the returned receiver.
+    pub fn channel() -> io::Result<(TxWithEventFd<Arg, Ret>, Self)> {
+        let (tx, rx) = mpsc::channel();
+        let rx = Self::new(rx)?;
+        let tx = TxWithEventFd::new(tx, rx.eventfd.try_clone()?);
+
+        Ok((tx, rx))
+    }
+
+    /// Resets the counter of the event file descriptor.
+    ///
+    /// This must be done before draining the receiver, so that a message sent
+    /// afterwards signals the event file descriptor again.
+    pub fn clear_eventfd(&self) -> io::Result<()> {
+        let mut buf = [0u8; 8];
+        // SAFETY: buf is valid for writes of its length.
+        let ret =
+            unsafe { libc::read(self.eventfd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+        if ret == -1 {
+            let e = io::Error::last_os_error();
+            if e.kind() != io::ErrorKind::WouldBlock {
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
     /// Consumes the `RxWithEventFd` and returns the inner receiver and event
     /// file descriptor.
     pub fn into_inner(self) -> (mpsc::Receiver<Invocation<Arg, Ret>>, OwnedFd) {
@@ -0,0 +1,89 @@
+//! Provides a sender that signals an event file descriptor (eventfd) after
+//! each message, pairing with [`RxWithEventFd`](crate::RxWithEventFd).
+
+use std::any::type_name;
+use std::mem::ManuallyDrop;
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::sync::{mpsc, Arc};
+use std::{fmt, io};
+
+use crate::Invocation;
+
+/// A sender that wraps an `mpsc::Sender` with an associated event file
+/// descriptor.
+pub struct TxWithEventFd<Arg, Ret> {
+    pub(crate) tx: ManuallyDrop<mpsc::Sender<Invocation<Arg, Ret>>>,
+    pub(crate) eventfd: Arc<OwnedFd>,
+}
+
+impl<Arg, Ret> TxWithEventFd<Arg, Ret> {
+    /// Creates a new `TxWithEventFd` that signals `eventfd` after each send.
+    pub fn new(tx: mpsc::Sender<Invocation<Arg, Ret>>, eventfd: OwnedFd) -> Self {
+        Self {
+            tx: ManuallyDrop::new(tx),
+            eventfd: Arc::new(eventfd),
+        }
+    }
+
+    /// Sends an invocation and then signals the event file descriptor.
+    pub fn send(
+        &self,
+        inv: Invocation<Arg, Ret>,
+    ) -> Result<(), mpsc::SendError<Invocation<Arg, Ret>>> {
+        self.tx.send(inv)?;
+
+        if let Err(e) = self.notify() {
+            log::warn!("Failed to signal eventfd: {e}");
+        }
+        Ok(())
+    }
+
+    fn notify(&self) -> io::Result<()> {
+        let buf = 1u64.to_ne_bytes();
+        // SAFETY: buf is valid for reads of its length.
+        let ret = unsafe { libc::write(self.eventfd.as_raw_fd(), buf.as_ptr().cast(), buf.len()) };
+        if ret == -1 {
+            let e = io::Error::last_os_error();
+            // The counter is about to overflow, so the receiver has been
+            // signalled anyway.
+            if e.kind() != io::ErrorKind::WouldBlock {
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Arg, Ret> Clone for TxWithEventFd<Arg, Ret> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: ManuallyDrop::new(mpsc::Sender::clone(&self.tx)),
+            eventfd: Arc::clone(&self.eventfd),
+        }
+    }
+}
+
+impl<Arg, Ret> Drop for TxWithEventFd<Arg, Ret> {
+    fn drop(&mut self) {
+        // SAFETY: tx is never used again after being dropped here.
+        unsafe { ManuallyDrop::drop(&mut self.tx) };
+
+        // Wake up the receiver, so that it notices when the last sender is
+        // dropped.
+        if let Err(e) = self.notify() {
+            log::warn!("Failed to signal eventfd: {e}");
+        }
+    }
+}
+
+impl<Arg, Ret> fmt::Debug for TxWithEventFd<Arg, Ret> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(&format!(
+            "TxWithEventFd<{}, {}>",
+            type_name::<Arg>(),
+            type_name::<Ret>()
+        ))
+        .field("eventfd", &self.eventfd)
+        .finish_non_exhaustive()
+    }
+}
@@ -1,9 +1,11 @@
+use std::fs::File;
 use std::io;
 use std::mem::size_of;
 use std::os::fd::OwnedFd;
+use std::os::unix::net::UnixStream;
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
-use rpcore_stream::codec::{Decode, Decoder, Encode, Encoder};
+use rpcore_stream::codec::{BufDecoder, BufEncoder, Decode, Decoder, Encode, Encoder};
 
 /// Represents a single message, containing a header, a payload, and optional file descriptors.
 struct Message {
@@ -130,4 +132,43 @@ impl Decode for Message {
     }
 }
 
-fn main() {}
+fn main() -> io::Result<()> {
+    let (mut tx, mut rx) = UnixStream::pair()?;
+
+    let payload = Bytes::from_static(b"hello, rpcore");
+    let file = File::open(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))?;
+    let message = Message {
+        header: Header {
+            magic: Header::MAGIC,
+            version: 1,
+            unused: [0; 3],
+            fd_len: 1,
+            payload_len: payload.len() as u32,
+        },
+        fds: vec![file.into()],
+        payload: payload.clone(),
+    };
+
+    let mut encoder = BufEncoder::new(&mut tx);
+    message.encode(&mut encoder);
+    encoder.into_result()?;
+
+    let mut decoder = BufDecoder::new();
+    let message = loop {
+        if let Some(message) = Message::decode(&mut decoder)? {
+            break message;
+        }
+        if decoder.fill(&mut rx)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+    };
+
+    assert_eq!(message.payload, payload);
+    println!(
+        "decoded {:?} with {} fds, payload: {:?}",
+        message.header,
+        message.fds.len(),
+        message.payload
+    );
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use std::io;
+use std::os::unix::net::UnixStream;
+use std::thread;
+use std::time::Duration;
+
+use bytes::Bytes;
+use rpcore::server::settings::HasHooks;
+use rpcore::server::singleplex::{Serve, Server};
+use rpcore::server::{Hooks, ShutdownBool};
+use rpcore::{Callback, Handler};
+use rpcore_stream::codec::{BufDecoder, BufEncoder, Decode, Encode, LengthDelimited};
+use rpcore_stream::{Correlated, CorrelatedSource, StreamSource};
+
+type Frame = LengthDelimited<Bytes>;
+
+/// Replies with the uppercased payload, after a delay that is shorter for
+/// later requests, so that replies complete in reverse order.
+struct SlowUpperHandler;
+
+impl Handler<Frame> for SlowUpperHandler {
+    type Ret = Frame;
+
+    fn handle(&mut self, arg: Frame, callback: impl Callback<Ret = Self::Ret>) {
+        thread::spawn(move || {
+            let (payload, _) = arg.into_parts();
+            let delay = 100 - 20 * u64::from(payload[payload.len() - 1] - b'0');
+            thread::sleep(Duration::from_millis(delay));
+
+            let reply = Bytes::from(payload.to_ascii_uppercase());
+            callback.call(Frame::new(reply).unwrap());
+        });
+    }
+}
+
+struct PrintHooks;
+
+impl Hooks for PrintHooks {
+    fn on_shutdown(&mut self) {
+        println!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        println!("Server got error: {e}");
+    }
+}
+
+struct Settings {
+    hooks: PrintHooks,
+}
+
+impl HasHooks for Settings {
+    type H = PrintHooks;
+
+    fn hooks(&mut self) -> &mut Self::H {
+        &mut self.hooks
+    }
+}
+
+fn main() -> io::Result<()> {
+    let (server_end, mut client_end) = UnixStream::pair()?;
+
+    let server_thread = thread::spawn(move || -> io::Result<()> {
+        let stream_source =
+            StreamSource::<Correlated<Frame>, Correlated<Frame>>::from_unix_stream(server_end)?;
+        let mut server = Server {
+            inv_src: CorrelatedSource::new(stream_source),
+            handler: SlowUpperHandler,
+            settings: Settings { hooks: PrintHooks },
+        };
+        Serve::serve(&mut server, &ShutdownBool::new());
+        Ok(())
+    });
+
+    // Pipeline all requests before reading any reply.
+    let requests = ["call 0", "call 1", "call 2", "call 3"];
+    for (id, request) in requests.iter().enumerate() {
+        let frame = Frame::new(Bytes::from_static(request.as_bytes())).unwrap();
+        let mut encoder = BufEncoder::new(&mut client_end);
+        Correlated::new(id as u64, frame).encode(&mut encoder);
+        encoder.into_result()?;
+    }
+
+    let mut decoder = BufDecoder::new();
+    let mut replied = 0;
+    while replied < requests.len() {
+        match Correlated::<Frame>::decode(&mut decoder) {
+            Ok(Some(reply)) => {
+                let request = requests[reply.id as usize];
+                println!("{request:?} -> {:?}", reply.inner.payload());
+                replied += 1;
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        }
+        if decoder.fill(&mut client_end)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+    }
+
+    drop(client_end);
+    server_thread.join().unwrap()
+}
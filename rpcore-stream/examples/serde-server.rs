@@ -0,0 +1,98 @@
+use std::io;
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+use rpcore::server::settings::HasHooks;
+use rpcore::server::singleplex::{Serve, Server};
+use rpcore::server::{Hooks, ShutdownBool};
+use rpcore::{Callback, Handler, HandlerBuilder};
+use rpcore_stream::codec::{BufDecoder, BufEncoder, Decode, Encode, Json, Serde, SerdeLayer};
+use rpcore_stream::StreamSource;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AddRequest {
+    a: i64,
+    b: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AddResponse {
+    sum: i64,
+}
+
+/// A plain handler, unaware of how its arguments are transported.
+struct AddHandler;
+
+impl Handler<AddRequest> for AddHandler {
+    type Ret = AddResponse;
+
+    fn handle(&mut self, arg: AddRequest, callback: impl Callback<Ret = Self::Ret>) {
+        callback.call(AddResponse { sum: arg.a + arg.b });
+    }
+}
+
+struct PrintHooks;
+
+impl Hooks for PrintHooks {
+    fn on_shutdown(&mut self) {
+        println!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        println!("Server got error: {e}");
+    }
+}
+
+struct Settings {
+    hooks: PrintHooks,
+}
+
+impl HasHooks for Settings {
+    type H = PrintHooks;
+
+    fn hooks(&mut self) -> &mut Self::H {
+        &mut self.hooks
+    }
+}
+
+fn main() -> io::Result<()> {
+    let (server_end, mut client_end) = UnixStream::pair()?;
+
+    let server_thread = thread::spawn(move || -> io::Result<()> {
+        let mut server = Server {
+            inv_src:
+                StreamSource::<Serde<AddRequest, Json>, Serde<AddResponse, Json>>::from_unix_stream(
+                    server_end,
+                )?,
+            handler: HandlerBuilder::new()
+                .layer(SerdeLayer::<Json>::new())
+                .handler(AddHandler),
+            settings: Settings { hooks: PrintHooks },
+        };
+        Serve::serve(&mut server, &ShutdownBool::new());
+        Ok(())
+    });
+
+    let mut decoder = BufDecoder::new();
+    for (a, b) in [(1, 2), (40, 2)] {
+        let mut encoder = BufEncoder::new(&mut client_end);
+        Serde::<_, Json>::new(AddRequest { a, b }).encode(&mut encoder);
+        encoder.into_result()?;
+
+        let response = loop {
+            match Serde::<AddResponse, Json>::decode(&mut decoder) {
+                Ok(Some(response)) => break response.into_inner(),
+                Ok(None) => {}
+                Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+            }
+            if decoder.fill(&mut client_end)? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+        };
+        println!("{a} + {b} = {}", response.sum);
+    }
+
+    drop(client_end);
+    server_thread.join().unwrap()
+}
@@ -0,0 +1,90 @@
+use std::io;
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+use rpcore::server::settings::HasHooks;
+use rpcore::server::singleplex::{Serve, Server};
+use rpcore::server::{Hooks, ShutdownBool};
+use rpcore::HandlerBuilder;
+use rpcore_stream::codec::{Json, Serde, SerdeLayer};
+use rpcore_stream::stream_client::StreamClient;
+use rpcore_stream::{Correlated, CorrelatedSource, StreamSource};
+use serde::{Deserialize, Serialize};
+
+#[rpcore::service(derive(Debug, Serialize, Deserialize))]
+trait Calculator {
+    fn add(&mut self, a: i64, b: i64) -> i64;
+
+    /// Divides `a` by `b`, failing if `b` is zero.
+    fn div(&mut self, a: i64, b: i64) -> Result<i64, String>;
+}
+
+struct Calc;
+
+impl Calculator for Calc {
+    fn add(&mut self, a: i64, b: i64) -> i64 {
+        a + b
+    }
+
+    fn div(&mut self, a: i64, b: i64) -> Result<i64, String> {
+        a.checked_div(b)
+            .ok_or_else(|| format!("cannot divide {a} by {b}"))
+    }
+}
+
+type Request = Serde<CalculatorRequest, Json>;
+type Response = Serde<CalculatorResponse, Json>;
+
+struct PrintHooks;
+
+impl Hooks for PrintHooks {
+    fn on_shutdown(&mut self) {
+        println!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        println!("Server got error: {e}");
+    }
+}
+
+struct Settings {
+    hooks: PrintHooks,
+}
+
+impl HasHooks for Settings {
+    type H = PrintHooks;
+
+    fn hooks(&mut self) -> &mut Self::H {
+        &mut self.hooks
+    }
+}
+
+fn main() -> io::Result<()> {
+    let (server_end, client_end) = UnixStream::pair()?;
+
+    let server_thread = thread::spawn(move || -> io::Result<()> {
+        let stream_source =
+            StreamSource::<Correlated<Request>, Correlated<Response>>::from_unix_stream(
+                server_end,
+            )?;
+        let mut server = Server {
+            inv_src: CorrelatedSource::new(stream_source),
+            handler: HandlerBuilder::new()
+                .layer(SerdeLayer::<Json>::new())
+                .handler(CalculatorHandler::new(Calc)),
+            settings: Settings { hooks: PrintHooks },
+        };
+        Serve::serve(&mut server, &ShutdownBool::new());
+        Ok(())
+    });
+
+    let client = CalculatorClient::new(StreamClient::<Request, Response>::from_unix_stream(
+        client_end,
+    )?);
+    println!("1 + 2 = {}", client.add(1, 2).unwrap());
+    println!("7 / 2 = {:?}", client.div(7, 2).unwrap());
+    println!("7 / 0 = {:?}", client.div(7, 0).unwrap());
+
+    drop(client);
+    server_thread.join().unwrap()
+}
@@ -0,0 +1,96 @@
+use std::io;
+use std::os::unix::net::UnixStream;
+use std::thread;
+use std::time::Duration;
+
+use bytes::Bytes;
+use rpcore::server::settings::HasHooks;
+use rpcore::server::singleplex::{Serve, Server};
+use rpcore::server::{Hooks, ShutdownBool};
+use rpcore::{Callback, Handler};
+use rpcore_stream::codec::LengthDelimited;
+use rpcore_stream::stream_client::{Error, StreamClient};
+use rpcore_stream::{Correlated, CorrelatedSource, StreamSource};
+
+type Frame = LengthDelimited<Bytes>;
+
+/// Echoes the payload after sleeping for the number of milliseconds it holds.
+struct SleepEchoHandler;
+
+impl Handler<Frame> for SleepEchoHandler {
+    type Ret = Frame;
+
+    fn handle(&mut self, arg: Frame, callback: impl Callback<Ret = Self::Ret>) {
+        thread::spawn(move || {
+            let (payload, _) = arg.into_parts();
+            let millis = std::str::from_utf8(&payload).unwrap().parse().unwrap();
+            thread::sleep(Duration::from_millis(millis));
+            callback.call(Frame::new(payload).unwrap());
+        });
+    }
+}
+
+struct PrintHooks;
+
+impl Hooks for PrintHooks {
+    fn on_shutdown(&mut self) {
+        println!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        println!("Server got error: {e}");
+    }
+}
+
+struct Settings {
+    hooks: PrintHooks,
+}
+
+impl HasHooks for Settings {
+    type H = PrintHooks;
+
+    fn hooks(&mut self) -> &mut Self::H {
+        &mut self.hooks
+    }
+}
+
+fn main() -> io::Result<()> {
+    let (server_end, client_end) = UnixStream::pair()?;
+
+    let server_thread = thread::spawn(move || -> io::Result<()> {
+        let stream_source =
+            StreamSource::<Correlated<Frame>, Correlated<Frame>>::from_unix_stream(server_end)?;
+        let mut server = Server {
+            inv_src: CorrelatedSource::new(stream_source),
+            handler: SleepEchoHandler,
+            settings: Settings { hooks: PrintHooks },
+        };
+        Serve::serve(&mut server, &ShutdownBool::new());
+        Ok(())
+    });
+
+    let client = StreamClient::<Frame, Frame>::from_unix_stream(client_end)?;
+    let callers: Vec<_> = ["300", "200", "100"]
+        .into_iter()
+        .map(|millis| {
+            let client = client.clone();
+            thread::spawn(move || {
+                let arg = Frame::new(Bytes::from_static(millis.as_bytes())).unwrap();
+                let ret = client.call(arg).unwrap();
+                println!("call {millis} returned {:?}", ret.payload());
+            })
+        })
+        .collect();
+    for caller in callers {
+        caller.join().unwrap();
+    }
+
+    let arg = Frame::new(Bytes::from_static(b"500")).unwrap();
+    match client.call_timeout(arg, Duration::from_millis(50)) {
+        Err(Error::ServerTimeout) => println!("call 500 timed out"),
+        other => println!("call 500 unexpectedly returned {other:?}"),
+    }
+
+    drop(client);
+    server_thread.join().unwrap()
+}
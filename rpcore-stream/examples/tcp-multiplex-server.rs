@@ -0,0 +1,155 @@
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use bytes::{Buf, BufMut, BytesMut};
+use rpcore::server::multiplex::{EpollReactor, ServeAccepting, Server};
+use rpcore::server::settings::{HasHooks, HasPolling};
+use rpcore::server::{Hooks, SetToken, Shutdown, ShutdownBool, Token};
+use rpcore::{Callback, Handler};
+use rpcore_core::invocation_source::Tokenized;
+use rpcore_stream::codec::{Decode, Decoder, Encode, Encoder};
+use rpcore_stream::{Acceptor, StreamSource};
+
+/// Wire format: `len: u32 LE`, followed by `len` bytes of UTF-8.
+struct Greeting {
+    token: Token,
+    name: String,
+}
+
+/// Wire format: the same as [`Greeting`].
+struct Reply(String);
+
+impl SetToken for Greeting {
+    fn set_token(&mut self, new: Token) {
+        self.token = new;
+    }
+}
+
+impl Decode for Greeting {
+    type Err = io::Error;
+
+    fn decode<D: Decoder>(mut decoder: D) -> Result<Option<Self>, Self::Err> {
+        if decoder.remaining() < 4 {
+            return Ok(None);
+        }
+        let len = decoder.peak(0, 4).get_u32_le() as usize;
+        if decoder.remaining() < 4 + len {
+            return Ok(None);
+        }
+
+        let name = decoder.peak(4, len);
+        decoder.advance(4 + len);
+        let name = String::from_utf8(name.to_vec())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Some(Greeting {
+            token: Token::default(),
+            name,
+        }))
+    }
+}
+
+impl Encode for Reply {
+    fn encode<C: Encoder>(self, mut encoder: C) {
+        let mut buf = BytesMut::with_capacity(4 + self.0.len());
+        buf.put_u32_le(self.0.len() as u32);
+        buf.put_slice(self.0.as_bytes());
+
+        encoder.write_bytes(buf.freeze());
+        encoder.finish();
+    }
+}
+
+struct GreetHandler;
+
+impl Handler<Greeting> for GreetHandler {
+    type Ret = Reply;
+
+    fn handle(&mut self, arg: Greeting, callback: impl Callback<Ret = Self::Ret>) {
+        let client: usize = arg.token.into();
+        callback.call(Reply(format!(
+            "Hello {}, you are client #{client}",
+            arg.name
+        )));
+    }
+}
+
+struct PrintHooks;
+
+impl Hooks for PrintHooks {
+    fn on_shutdown(&mut self) {
+        println!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        println!("Server got error: {e}");
+    }
+}
+
+struct Settings {
+    polling: Option<Duration>,
+    hooks: PrintHooks,
+}
+
+impl HasPolling for Settings {
+    fn polling(&self) -> &Option<Duration> {
+        &self.polling
+    }
+}
+
+impl HasHooks for Settings {
+    type H = PrintHooks;
+
+    fn hooks(&mut self) -> &mut Self::H {
+        &mut self.hooks
+    }
+}
+
+fn main() -> io::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let shutdown = ShutdownBool::new();
+
+    let server_thread = {
+        let shutdown = shutdown.clone();
+        thread::spawn(move || -> io::Result<()> {
+            let mut acceptor = Acceptor::new(listener, |stream, token| {
+                let source = StreamSource::<Greeting, Reply, _, _>::from_tcp_stream(stream)?;
+                Ok(Tokenized::new(token, source))
+            })?;
+            let settings = Settings {
+                polling: None,
+                hooks: PrintHooks,
+            };
+            let mut server = Server::new(EpollReactor::new(64)?, GreetHandler, settings);
+            server.serve_accepting(&mut acceptor, &shutdown);
+            Ok(())
+        })
+    };
+
+    let clients: Vec<_> = ["alice", "bob", "carol"]
+        .into_iter()
+        .map(|name| thread::spawn(move || greet(TcpStream::connect(addr)?, name)))
+        .collect();
+    for client in clients {
+        client.join().unwrap()?;
+    }
+
+    shutdown.shutdown();
+    server_thread.join().unwrap()
+}
+
+fn greet(mut stream: TcpStream, name: &str) -> io::Result<()> {
+    for _ in 0..2 {
+        stream.write_all(&(name.len() as u32).to_le_bytes())?;
+        stream.write_all(name.as_bytes())?;
+
+        let mut len = [0u8; 4];
+        stream.read_exact(&mut len)?;
+        let mut reply = vec![0u8; u32::from_le_bytes(len) as usize];
+        stream.read_exact(&mut reply)?;
+        println!("{name} got: {}", String::from_utf8_lossy(&reply));
+    }
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use std::fs::File;
+use std::io::{self, IoSlice, Read};
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+use bytes::{Buf, BufMut, BytesMut};
+use rpcore::server::settings::HasHooks;
+use rpcore::server::singleplex::{Serve, Server};
+use rpcore::server::{Hooks, ShutdownBool};
+use rpcore::{Callback, Handler};
+use rpcore_stream::codec::{Decode, Decoder, Encode, Encoder};
+use rpcore_stream::extended_io::Write as _;
+use rpcore_stream::StreamSource;
+
+/// Asks the server for the sizes of the files behind the passed fds.
+///
+/// Wire format: `fd_count: u32 LE`, with `fd_count` fds attached.
+struct Request {
+    files: Vec<OwnedFd>,
+}
+
+/// Wire format: `count: u32 LE`, followed by `count` sizes as `u64 LE`.
+struct Response {
+    sizes: Vec<u64>,
+}
+
+impl Decode for Request {
+    type Err = io::Error;
+
+    fn decode<D: Decoder>(mut decoder: D) -> Result<Option<Self>, Self::Err> {
+        if decoder.remaining() < 4 {
+            return Ok(None);
+        }
+        let fd_count = decoder.peak(0, 4).get_u32_le() as usize;
+        if decoder.remaining_fds() < fd_count {
+            return Ok(None);
+        }
+
+        decoder.advance(4);
+        let mut files = Vec::with_capacity(fd_count);
+        decoder.read_fds(&mut files, fd_count);
+        Ok(Some(Request { files }))
+    }
+}
+
+impl Encode for Response {
+    fn encode<C: Encoder>(self, mut encoder: C) {
+        let mut buf = BytesMut::with_capacity(4 + 8 * self.sizes.len());
+        buf.put_u32_le(self.sizes.len() as u32);
+        for size in self.sizes {
+            buf.put_u64_le(size);
+        }
+
+        encoder.write_bytes(buf.freeze());
+        encoder.finish();
+    }
+}
+
+struct FileSizeHandler;
+
+impl Handler<Request> for FileSizeHandler {
+    type Ret = Response;
+
+    fn handle(&mut self, arg: Request, callback: impl Callback<Ret = Self::Ret>) {
+        let sizes = arg
+            .files
+            .into_iter()
+            .map(|fd| File::from(fd).metadata().map_or(0, |m| m.len()))
+            .collect();
+        callback.call(Response { sizes });
+    }
+}
+
+struct PrintHooks;
+
+impl Hooks for PrintHooks {
+    fn on_shutdown(&mut self) {
+        println!("Server shutdown");
+    }
+
+    fn on_error(&mut self, e: &dyn std::error::Error) {
+        println!("Server got error: {e}");
+    }
+}
+
+struct Settings {
+    hooks: PrintHooks,
+}
+
+impl HasHooks for Settings {
+    type H = PrintHooks;
+
+    fn hooks(&mut self) -> &mut Self::H {
+        &mut self.hooks
+    }
+}
+
+fn main() -> io::Result<()> {
+    let (server_end, mut client_end) = UnixStream::pair()?;
+
+    let server_thread = thread::spawn(move || -> io::Result<()> {
+        let mut server = Server {
+            inv_src: StreamSource::<Request, Response>::from_unix_stream(server_end)?,
+            handler: FileSizeHandler,
+            settings: Settings { hooks: PrintHooks },
+        };
+        Serve::serve(&mut server, &ShutdownBool::new());
+        Ok(())
+    });
+
+    const CARGO_TOML: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+    const THIS_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/unix-server.rs");
+
+    for paths in [&[CARGO_TOML][..], &[CARGO_TOML, THIS_FILE]] {
+        let files = paths
+            .iter()
+            .map(File::open)
+            .collect::<io::Result<Vec<_>>>()?;
+        let fds: Vec<_> = files.iter().map(|f| f.as_raw_fd()).collect();
+
+        let header = (fds.len() as u32).to_le_bytes();
+        client_end.write_vectored_with_fds(&[IoSlice::new(&header)], &fds)?;
+
+        let mut count = [0u8; 4];
+        client_end.read_exact(&mut count)?;
+        for path in paths {
+            let mut size = [0u8; 8];
+            client_end.read_exact(&mut size)?;
+            println!("{path}: {} bytes", u64::from_le_bytes(size));
+        }
+    }
+
+    drop(client_end);
+    server_thread.join().unwrap()
+}
@@ -0,0 +1,160 @@
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::os::fd::AsFd;
+use std::os::unix::net::{UnixListener, UnixStream};
+
+use rpcore_core::invocation_source::readiness::Accept;
+use rpcore_core::server::{AllocToken, Token, UnsyncTokenAllocator};
+
+/// A listening socket that accepts connected streams.
+pub trait Listener: AsFd {
+    type Stream;
+
+    /// Accepts a new connection, which is put into non-blocking mode.
+    fn accept(&self) -> io::Result<Self::Stream>;
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()>;
+}
+
+impl Listener for TcpListener {
+    type Stream = TcpStream;
+
+    fn accept(&self) -> io::Result<Self::Stream> {
+        let (stream, _) = TcpListener::accept(self)?;
+        stream.set_nonblocking(true)?;
+        Ok(stream)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        TcpListener::set_nonblocking(self, nonblocking)
+    }
+}
+
+impl Listener for UnixListener {
+    type Stream = UnixStream;
+
+    fn accept(&self) -> io::Result<Self::Stream> {
+        let (stream, _) = UnixListener::accept(self)?;
+        stream.set_nonblocking(true)?;
+        Ok(stream)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        UnixListener::set_nonblocking(self, nonblocking)
+    }
+}
+
+#[cfg(feature = "socket2")]
+impl Listener for socket2::Socket {
+    type Stream = socket2::Socket;
+
+    fn accept(&self) -> io::Result<Self::Stream> {
+        let (socket, _) = socket2::Socket::accept(self)?;
+        socket.set_nonblocking(true)?;
+        Ok(socket)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        socket2::Socket::set_nonblocking(self, nonblocking)
+    }
+}
+
+/// Accepts connections from a [`Listener`] and turns each of them into an
+/// invocation source identified by a newly allocated [`Token`].
+///
+/// Used with
+/// [`ServeAccepting`](rpcore_core::server::multiplex::ServeAccepting), one
+/// multiplex server serves all connections accepted from the listener.
+/// `make_source` typically wraps the connection into a
+/// [`StreamSource`](crate::StreamSource), and the result into a
+/// [`Tokenized`](rpcore_core::invocation_source::Tokenized) so that the
+/// handler can tell the clients apart.
+pub struct Acceptor<L, F, A = UnsyncTokenAllocator> {
+    pub(crate) listener: L,
+    make_source: F,
+    token_allocator: A,
+}
+
+impl<L, F, S> Acceptor<L, F>
+where
+    L: Listener,
+    F: FnMut(L::Stream, Token) -> io::Result<S>,
+{
+    /// Creates a new `Acceptor` and puts `listener` into non-blocking mode.
+    pub fn new(listener: L, make_source: F) -> io::Result<Self> {
+        Self::with_token_allocator(listener, make_source, Token::new_unsync_allocator())
+    }
+}
+
+impl<L, F, A, S> Acceptor<L, F, A>
+where
+    L: Listener,
+    F: FnMut(L::Stream, Token) -> io::Result<S>,
+    A: AllocToken,
+{
+    /// Creates a new `Acceptor` that allocates tokens from `token_allocator`,
+    /// and puts `listener` into non-blocking mode.
+    ///
+    /// Sharing a [`SyncTokenAllocator`](rpcore_core::server::SyncTokenAllocator)
+    /// keeps tokens unique across several acceptors.
+    pub fn with_token_allocator(
+        listener: L,
+        make_source: F,
+        token_allocator: A,
+    ) -> io::Result<Self> {
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            make_source,
+            token_allocator,
+        })
+    }
+}
+
+impl<L, F, A> Acceptor<L, F, A> {
+    pub fn get_ref(&self) -> &L {
+        &self.listener
+    }
+
+    pub fn into_inner(self) -> L {
+        self.listener
+    }
+}
+
+impl<L, F, A, S> Accept for Acceptor<L, F, A>
+where
+    L: Listener,
+    F: FnMut(L::Stream, Token) -> io::Result<S>,
+    A: AllocToken,
+{
+    type Source = S;
+    type AcceptErr = io::Error;
+
+    fn try_accept(&mut self) -> Result<Option<Self::Source>, Self::AcceptErr> {
+        let stream = loop {
+            match self.listener.accept() {
+                Ok(stream) => break stream,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        };
+
+        let token = self
+            .token_allocator
+            .alloc_token()
+            .ok_or_else(|| io::Error::other("too many connections"))?;
+        (self.make_source)(stream, token).map(Some)
+    }
+}
+
+impl<L, F, A> std::fmt::Debug for Acceptor<L, F, A>
+where
+    L: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Acceptor")
+            .field("listener", &self.listener)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,6 +1,97 @@
-use std::os::fd::RawFd;
+use bytes::{Bytes, BytesMut};
 
 pub trait Recv {
     fn area(&mut self, size_hint: Option<usize>) -> &mut [u8];
-    fn area_with_fds(&mut self, size_hint: Option<usize>) -> (&mut [u8], &mut [RawFd]);
+}
+
+/// The smallest area handed out when no size hint is given.
+const MIN_AREA_SIZE: usize = 4096;
+
+/// The default size of the blocks allocated by a [`RecvArena`].
+const DEFAULT_BLOCK_SIZE: usize = 64 * 1024;
+
+/// The number of retired blocks kept for reuse.
+const POOL_SIZE: usize = 4;
+
+/// The maximum number of file descriptors in one `SCM_RIGHTS` message,
+/// `SCM_MAX_FD` on Linux.
+pub(crate) const MAX_FDS: usize = 253;
+
+/// A growable, pooled receive buffer.
+///
+/// Reads go into the spare area handed out by [`Recv::area`], and the filled
+/// part is then carved off as [`Bytes`] with [`RecvArena::commit`]. Carved
+/// `Bytes` share the block they were received into. Once all of them are
+/// dropped, the block is reused for later reads instead of allocating a new
+/// one.
+#[derive(Debug)]
+pub struct RecvArena {
+    block: BytesMut,
+    retired: Vec<BytesMut>,
+    block_size: usize,
+}
+
+impl Default for RecvArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecvArena {
+    pub fn new() -> Self {
+        Self::with_block_size(DEFAULT_BLOCK_SIZE)
+    }
+
+    pub fn with_block_size(block_size: usize) -> Self {
+        assert!(block_size > 0, "Block size must be greater than 0.");
+
+        Self {
+            block: BytesMut::new(),
+            retired: Vec::new(),
+            block_size,
+        }
+    }
+
+    /// Carves the first `n` bytes of the area last handed out into `Bytes`.
+    pub fn commit(&mut self, n: usize) -> Bytes {
+        assert!(n <= self.block.len(), "Cannot commit more than the area.");
+
+        self.block.split_to(n).freeze()
+    }
+
+    /// Makes sure the current block has at least `size` bytes of initialized
+    /// spare area, switching to a reused or new block if it does not.
+    fn ensure_area(&mut self, size: usize) {
+        if self.block.len() >= size {
+            return;
+        }
+
+        let size = size.max(self.block_size);
+        let mut old = std::mem::take(&mut self.block);
+        old.clear();
+        self.retired.push(old);
+
+        let reusable = self
+            .retired
+            .iter_mut()
+            .position(|block| block.try_reclaim(size));
+        self.block = match reusable {
+            Some(i) => self.retired.swap_remove(i),
+            None => BytesMut::with_capacity(size),
+        };
+        if self.retired.len() > POOL_SIZE {
+            self.retired.remove(0);
+        }
+
+        // Only initialized memory can be handed out, this is done once per
+        // block rather than once per read.
+        self.block.resize(size, 0);
+    }
+}
+
+impl Recv for RecvArena {
+    fn area(&mut self, size_hint: Option<usize>) -> &mut [u8] {
+        self.ensure_area(size_hint.unwrap_or(MIN_AREA_SIZE).max(1));
+        &mut self.block[..]
+    }
 }
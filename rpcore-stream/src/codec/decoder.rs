@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+use std::io::{self, IoSliceMut};
+use std::os::fd::OwnedFd;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::buf::{Recv, RecvArena};
+use crate::codec::Decoder;
+use crate::extended_io;
+
+/// A [`Decoder`] over a rope of received [`Bytes`] chunks and a FIFO of
+/// received file descriptors.
+///
+/// Chunks are never merged, so peeking or reading a range that lies within a
+/// single chunk does not copy.
+#[derive(Debug, Default)]
+pub struct BufDecoder {
+    chunks: VecDeque<Bytes>,
+    remaining: usize,
+    fds: VecDeque<OwnedFd>,
+    arena: RecvArena,
+}
+
+impl BufDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `BufDecoder` that receives into `arena` in [`fill`].
+    ///
+    /// [`fill`]: BufDecoder::fill
+    pub fn with_arena(arena: RecvArena) -> Self {
+        Self {
+            arena,
+            ..Default::default()
+        }
+    }
+
+    /// Appends a chunk of bytes to the end of the buffer.
+    pub fn push_bytes(&mut self, bytes: Bytes) {
+        if !bytes.is_empty() {
+            self.remaining += bytes.len();
+            self.chunks.push_back(bytes);
+        }
+    }
+
+    /// Appends file descriptors to the end of the fd queue.
+    pub fn push_fds(&mut self, fds: impl IntoIterator<Item = OwnedFd>) {
+        self.fds.extend(fds);
+    }
+
+    /// Reads once from `reader`, appending the received bytes and file
+    /// descriptors to the buffer.
+    ///
+    /// Returns the number of bytes read, `0` means end of stream.
+    pub fn fill<R: extended_io::Read>(&mut self, reader: &mut R) -> io::Result<usize> {
+        let area = self.arena.area(None);
+        let (n, fds) = reader.read_vectored_with_fds(&mut [IoSliceMut::new(area)])?;
+
+        let bytes = self.arena.commit(n);
+        self.push_bytes(bytes);
+        self.push_fds(fds);
+        Ok(n)
+    }
+}
+
+impl Decoder for BufDecoder {
+    fn peak(&self, offset: usize, count: usize) -> Bytes {
+        assert!(
+            offset + count <= self.remaining,
+            "Not enough bytes to peak."
+        );
+        if count == 0 {
+            return Bytes::new();
+        }
+
+        let mut chunks = self.chunks.iter();
+        let mut offset = offset;
+        let first = loop {
+            let chunk = chunks.next().unwrap();
+            if offset < chunk.len() {
+                break chunk;
+            }
+            offset -= chunk.len();
+        };
+
+        if offset + count <= first.len() {
+            return first.slice(offset..offset + count);
+        }
+
+        // The range spans several chunks and has to be copied.
+        let mut buf = BytesMut::with_capacity(count);
+        buf.put_slice(&first[offset..]);
+        for chunk in chunks {
+            let n = chunk.len().min(count - buf.len());
+            buf.put_slice(&chunk[..n]);
+            if buf.len() == count {
+                break;
+            }
+        }
+        buf.freeze()
+    }
+
+    fn advance(&mut self, count: usize) {
+        assert!(count <= self.remaining, "Not enough bytes to advance.");
+
+        self.remaining -= count;
+        let mut count = count;
+        while count > 0 {
+            let chunk = self.chunks.front_mut().unwrap();
+            if count < chunk.len() {
+                chunk.advance(count);
+                return;
+            }
+            count -= chunk.len();
+            self.chunks.pop_front();
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    fn read(&mut self, buf: &mut Vec<Bytes>, count: usize) {
+        assert!(count <= self.remaining, "Not enough bytes to read.");
+
+        self.remaining -= count;
+        let mut count = count;
+        while count > 0 {
+            let chunk = self.chunks.front_mut().unwrap();
+            if count < chunk.len() {
+                buf.push(chunk.split_to(count));
+                return;
+            }
+            count -= chunk.len();
+            buf.push(self.chunks.pop_front().unwrap());
+        }
+    }
+
+    fn remaining_fds(&self) -> usize {
+        self.fds.len()
+    }
+
+    fn read_fds(&mut self, buf: &mut Vec<OwnedFd>, count: usize) {
+        assert!(count <= self.fds.len(), "Not enough fds to read.");
+
+        buf.extend(self.fds.drain(..count));
+    }
+}
@@ -0,0 +1,192 @@
+use std::io::{self, IoSlice};
+use std::os::fd::{AsFd, AsRawFd, OwnedFd, RawFd};
+
+use bytes::{Buf, Bytes};
+
+use crate::codec::Encoder;
+use crate::extended_io::{self, cvt};
+
+/// The maximum number of buffers passed to a single `writev`, `IOV_MAX` on
+/// Linux.
+const MAX_IOVS: usize = 1024;
+
+/// An [`Encoder`] that batches the bytes and file descriptors of a message
+/// and writes them with as few syscalls as possible.
+///
+/// Buffered bytes are written with a single
+/// [`write_vectored_with_fds`](extended_io::Write::write_vectored_with_fds),
+/// carrying all file descriptors written since the previous flush. Zero-copy
+/// writes first flush the buffered bytes and then use `sendfile(2)`, so the
+/// data is written in order.
+///
+/// The encoder is used through `&mut BufEncoder`, so that the outcome can be
+/// retrieved with [`BufEncoder::into_result`] after the message is encoded:
+///
+/// ```no_run
+/// # use std::os::unix::net::UnixStream;
+/// # use rpcore_stream::codec::{BufEncoder, Encode};
+/// # fn send(msg: impl Encode, mut stream: UnixStream) -> std::io::Result<()> {
+/// let mut encoder = BufEncoder::new(&mut stream);
+/// msg.encode(&mut encoder);
+/// encoder.into_result()
+/// # }
+/// ```
+pub struct BufEncoder<'a, W> {
+    writer: &'a mut W,
+    chunks: Vec<Bytes>,
+    fds: Vec<OwnedFd>,
+    error: Option<io::Error>,
+    finished: bool,
+}
+
+impl<'a, W> BufEncoder<'a, W>
+where
+    W: extended_io::Write + AsFd,
+{
+    pub fn new(writer: &'a mut W) -> Self {
+        Self {
+            writer,
+            chunks: Vec::new(),
+            fds: Vec::new(),
+            error: None,
+            finished: false,
+        }
+    }
+
+    /// Returns the first error that occurred while writing, or an error if the
+    /// message was not finished, e.g. because it failed to encode.
+    pub fn into_result(self) -> io::Result<()> {
+        match self.error {
+            Some(e) => Err(e),
+            None if !self.finished => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "message was not finished",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes all buffered bytes along with the buffered file descriptors,
+    /// retrying on partial writes.
+    fn flush(&mut self) -> io::Result<()> {
+        if self.chunks.is_empty() {
+            if self.fds.is_empty() {
+                return Ok(());
+            }
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "file descriptors must be sent along with bytes",
+            ));
+        }
+
+        let raw_fds: Vec<RawFd> = self.fds.iter().map(|fd| fd.as_raw_fd()).collect();
+        let mut fds = &raw_fds[..];
+        let mut chunks = &mut self.chunks[..];
+
+        while !chunks.is_empty() {
+            let bufs: Vec<IoSlice<'_>> = chunks
+                .iter()
+                .take(MAX_IOVS)
+                .map(|chunk| IoSlice::new(chunk))
+                .collect();
+
+            let mut n = match self.writer.write_vectored_with_fds(&bufs, fds) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    extended_io::wait_fd(self.writer.as_fd(), libc::POLLOUT)?;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            // File descriptors are sent along with the first written byte.
+            fds = &[];
+
+            while n > 0 {
+                let chunk = &mut chunks[0];
+                if n < chunk.len() {
+                    chunk.advance(n);
+                    break;
+                }
+                n -= chunk.len();
+                chunks = &mut chunks[1..];
+            }
+        }
+
+        self.chunks.clear();
+        self.fds.clear();
+        Ok(())
+    }
+
+    fn sendfile(&mut self, fd: RawFd, offset: usize, count: usize) -> io::Result<()> {
+        let out_fd = self.writer.as_fd().as_raw_fd();
+        let mut offset = offset as libc::off_t;
+        let mut count = count;
+
+        while count > 0 {
+            // SAFETY: Safe to call `sendfile` with valid fds and offset.
+            let ret = unsafe { libc::sendfile(out_fd, fd, &mut offset, count) };
+            match cvt(ret) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => count -= n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    extended_io::wait_fd(self.writer.as_fd(), libc::POLLOUT)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, result: io::Result<()>) {
+        if let Err(e) = result {
+            self.error = Some(e);
+            self.chunks.clear();
+            self.fds.clear();
+        }
+    }
+}
+
+impl<W> Encoder for &mut BufEncoder<'_, W>
+where
+    W: extended_io::Write + AsFd,
+{
+    fn write_bytes<B: Buf>(&mut self, mut bytes: B) {
+        if self.error.is_some() || !bytes.has_remaining() {
+            return;
+        }
+        // This does not copy if `B` is `Bytes`.
+        let chunk = bytes.copy_to_bytes(bytes.remaining());
+        self.chunks.push(chunk);
+    }
+
+    fn write_zcopy_from_fd(&mut self, fd: impl AsFd, offset: usize, count: usize) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let result = self
+            .flush()
+            .and_then(|()| self.sendfile(fd.as_fd().as_raw_fd(), offset, count));
+        self.record(result);
+    }
+
+    fn write_fds(&mut self, fds: impl IntoIterator<Item = OwnedFd>) {
+        if self.error.is_some() {
+            return;
+        }
+        self.fds.extend(fds);
+    }
+
+    fn finish(self) {
+        self.finished = true;
+        if self.error.is_some() {
+            return;
+        }
+
+        let result = self.flush();
+        self.record(result);
+    }
+}
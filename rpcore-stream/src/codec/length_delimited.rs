@@ -0,0 +1,413 @@
+use std::marker::PhantomData;
+use std::os::fd::OwnedFd;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::buf::MAX_FDS;
+use crate::codec::{Decode, Decoder, Encode, Encoder};
+
+/// The width of the length and fd-count fields of a frame header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixWidth {
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl PrefixWidth {
+    pub const fn size(self) -> usize {
+        match self {
+            PrefixWidth::U8 => 1,
+            PrefixWidth::U16 => 2,
+            PrefixWidth::U32 => 4,
+            PrefixWidth::U64 => 8,
+        }
+    }
+
+    /// The largest value that fits in a field of this width.
+    pub const fn max_value(self) -> u64 {
+        match self {
+            PrefixWidth::U8 => u8::MAX as u64,
+            PrefixWidth::U16 => u16::MAX as u64,
+            PrefixWidth::U32 => u32::MAX as u64,
+            PrefixWidth::U64 => u64::MAX,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+/// Describes the header of a [`LengthDelimited`] frame.
+///
+/// A header consists of [`MAGIC`], followed by the payload length and, if
+/// [`FD_COUNT`] is set, the number of attached file descriptors. Both fields
+/// are [`WIDTH`] wide and in [`ENDIAN`] byte order.
+///
+/// [`MAGIC`]: FrameFormat::MAGIC
+/// [`FD_COUNT`]: FrameFormat::FD_COUNT
+/// [`WIDTH`]: FrameFormat::WIDTH
+/// [`ENDIAN`]: FrameFormat::ENDIAN
+pub trait FrameFormat {
+    const MAGIC: &'static [u8] = &[];
+    const WIDTH: PrefixWidth = PrefixWidth::U32;
+    const ENDIAN: Endian = Endian::Little;
+    /// The maximum payload size, frames with larger payloads are rejected.
+    const MAX_FRAME_SIZE: usize = 8 * 1024 * 1024;
+    /// Whether the header carries an fd-count field. File descriptors can
+    /// only be sent along with frames if it does.
+    const FD_COUNT: bool = false;
+
+    /// The size of the header in bytes.
+    fn header_size() -> usize {
+        Self::MAGIC.len() + Self::WIDTH.size() * (1 + Self::FD_COUNT as usize)
+    }
+}
+
+/// A frame with no magic, a `u32` little-endian length and no fd-count field.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultFormat;
+
+impl FrameFormat for DefaultFormat {}
+
+/// An error when building or decoding a [`LengthDelimited`] frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// The payload is larger than the maximum frame size or than what the
+    /// length field can hold.
+    Oversize { size: u64, max: usize },
+    /// The frame does not start with the expected magic.
+    BadMagic,
+    /// More file descriptors than fit in one message or in the fd-count
+    /// field.
+    TooManyFds { count: u64 },
+    /// File descriptors were attached, but the format has no fd-count field.
+    FdsNotSupported,
+}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameError::Oversize { size, max } => {
+                write!(
+                    f,
+                    "frame of {size} bytes exceeds the maximum of {max} bytes"
+                )
+            }
+            FrameError::BadMagic => write!(f, "frame has a bad magic"),
+            FrameError::TooManyFds { count } => {
+                write!(f, "frame has too many file descriptors: {count}")
+            }
+            FrameError::FdsNotSupported => {
+                write!(f, "frame format does not support file descriptors")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// A frame made of a length-prefixed payload and optionally attached file
+/// descriptors, with its header described by `F`.
+///
+/// Payloads are encoded from and decoded into `T` through [`Bytes`], which
+/// avoids copies when `T` is `Bytes`:
+///
+/// ```
+/// # use std::os::unix::net::UnixStream;
+/// # use bytes::Bytes;
+/// # use rpcore_stream::codec::{BufDecoder, BufEncoder, Decode, Encode, LengthDelimited};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let (mut tx, mut rx) = UnixStream::pair()?;
+///
+/// let mut encoder = BufEncoder::new(&mut tx);
+/// LengthDelimited::<Bytes>::new(Bytes::from_static(b"hello"))?.encode(&mut encoder);
+/// encoder.into_result()?;
+///
+/// let mut decoder = BufDecoder::new();
+/// decoder.fill(&mut rx)?;
+/// let frame = LengthDelimited::<Bytes>::decode(&mut decoder)?.unwrap();
+/// assert_eq!(frame.payload(), &b"hello"[..]);
+/// # Ok(())
+/// # }
+/// ```
+pub struct LengthDelimited<T = Bytes, F = DefaultFormat> {
+    payload: T,
+    fds: Vec<OwnedFd>,
+    _format: PhantomData<fn() -> F>,
+}
+
+impl<T, F> LengthDelimited<T, F>
+where
+    T: AsRef<[u8]>,
+    F: FrameFormat,
+{
+    pub fn new(payload: T) -> Result<Self, FrameError> {
+        Self::with_fds(payload, Vec::new())
+    }
+
+    /// Creates a frame that carries `fds` along with `payload`.
+    ///
+    /// Fails if `F` has no fd-count field and `fds` is not empty.
+    pub fn with_fds(payload: T, fds: Vec<OwnedFd>) -> Result<Self, FrameError> {
+        check_size::<F>(payload.as_ref().len() as u64)?;
+        if !fds.is_empty() && !F::FD_COUNT {
+            return Err(FrameError::FdsNotSupported);
+        }
+        check_fds::<F>(fds.len() as u64)?;
+
+        Ok(Self {
+            payload,
+            fds,
+            _format: PhantomData,
+        })
+    }
+}
+
+impl<T, F> LengthDelimited<T, F> {
+    pub fn payload(&self) -> &T {
+        &self.payload
+    }
+
+    pub fn fds(&self) -> &[OwnedFd] {
+        &self.fds
+    }
+
+    pub fn into_parts(self) -> (T, Vec<OwnedFd>) {
+        (self.payload, self.fds)
+    }
+}
+
+impl<T, F> std::fmt::Debug for LengthDelimited<T, F>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LengthDelimited")
+            .field("payload", &self.payload)
+            .field("fds", &self.fds)
+            .finish()
+    }
+}
+
+fn check_size<F: FrameFormat>(size: u64) -> Result<(), FrameError> {
+    let max = F::MAX_FRAME_SIZE.min(F::WIDTH.max_value().try_into().unwrap_or(usize::MAX));
+    if size > max as u64 {
+        return Err(FrameError::Oversize { size, max });
+    }
+    Ok(())
+}
+
+fn check_fds<F: FrameFormat>(count: u64) -> Result<(), FrameError> {
+    if count > MAX_FDS as u64 || count > F::WIDTH.max_value() {
+        return Err(FrameError::TooManyFds { count });
+    }
+    Ok(())
+}
+
+fn put_field<F: FrameFormat>(buf: &mut BytesMut, value: u64) {
+    match F::ENDIAN {
+        Endian::Big => buf.put_uint(value, F::WIDTH.size()),
+        Endian::Little => buf.put_uint_le(value, F::WIDTH.size()),
+    }
+}
+
+fn get_field<F: FrameFormat>(buf: &mut Bytes) -> u64 {
+    match F::ENDIAN {
+        Endian::Big => buf.get_uint(F::WIDTH.size()),
+        Endian::Little => buf.get_uint_le(F::WIDTH.size()),
+    }
+}
+
+impl<T, F> Encode for LengthDelimited<T, F>
+where
+    T: Into<Bytes>,
+    F: FrameFormat,
+{
+    fn encode<C: Encoder>(self, mut encoder: C) {
+        let payload = self.payload.into();
+
+        let mut header = BytesMut::with_capacity(F::header_size());
+        header.put_slice(F::MAGIC);
+        put_field::<F>(&mut header, payload.len() as u64);
+        if F::FD_COUNT {
+            put_field::<F>(&mut header, self.fds.len() as u64);
+        }
+
+        encoder.write_bytes(header.freeze());
+        encoder.write_bytes(payload);
+        encoder.write_fds(self.fds);
+        encoder.finish();
+    }
+}
+
+impl<T, F> Decode for LengthDelimited<T, F>
+where
+    T: From<Bytes>,
+    F: FrameFormat,
+{
+    type Err = FrameError;
+
+    fn decode<D: Decoder>(mut decoder: D) -> Result<Option<Self>, Self::Err> {
+        // A bad magic is reported as soon as its first bytes arrive.
+        let magic_len = F::MAGIC.len().min(decoder.remaining());
+        if decoder.peak(0, magic_len) != F::MAGIC[..magic_len] {
+            return Err(FrameError::BadMagic);
+        }
+
+        let header_size = F::header_size();
+        if decoder.remaining() < header_size {
+            return Ok(None);
+        }
+
+        let mut header = decoder.peak(F::MAGIC.len(), header_size - F::MAGIC.len());
+        let len = get_field::<F>(&mut header);
+        check_size::<F>(len)?;
+        let fd_count = if F::FD_COUNT {
+            let count = get_field::<F>(&mut header);
+            check_fds::<F>(count)?;
+            count as usize
+        } else {
+            0
+        };
+
+        let len = len as usize;
+        if decoder.remaining() < header_size + len || decoder.remaining_fds() < fd_count {
+            return Ok(None);
+        }
+
+        decoder.advance(header_size);
+        let payload = decoder.peak(0, len);
+        decoder.advance(len);
+        let mut fds = Vec::with_capacity(fd_count);
+        decoder.read_fds(&mut fds, fd_count);
+
+        Ok(Some(Self {
+            payload: payload.into(),
+            fds,
+            _format: PhantomData,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::os::unix::net::UnixStream;
+
+    use super::*;
+    use crate::codec::{BufDecoder, BufEncoder};
+
+    struct Small;
+
+    impl FrameFormat for Small {
+        const MAX_FRAME_SIZE: usize = 4;
+    }
+
+    /// A format whose `u8` fields limit both the length and the fd count.
+    struct WithFds;
+
+    impl FrameFormat for WithFds {
+        const MAGIC: &'static [u8] = b"RP";
+        const WIDTH: PrefixWidth = PrefixWidth::U8;
+        const FD_COUNT: bool = true;
+    }
+
+    fn null_fd() -> OwnedFd {
+        File::open("/dev/null").unwrap().into()
+    }
+
+    fn decode<F: FrameFormat>(
+        bytes: &'static [u8],
+    ) -> Result<Option<LengthDelimited<Bytes, F>>, FrameError> {
+        let mut decoder = BufDecoder::new();
+        decoder.push_bytes(Bytes::from_static(bytes));
+        LengthDelimited::decode(&mut decoder)
+    }
+
+    #[test]
+    fn rejects_oversize_payloads() {
+        assert!(LengthDelimited::<_, Small>::new(Bytes::from_static(b"1234")).is_ok());
+        assert_eq!(
+            LengthDelimited::<_, Small>::new(Bytes::from_static(b"12345")).unwrap_err(),
+            FrameError::Oversize { size: 5, max: 4 }
+        );
+        // The length field is narrower than the maximum frame size.
+        assert_eq!(
+            LengthDelimited::<_, WithFds>::new(Bytes::from(vec![0; 256])).unwrap_err(),
+            FrameError::Oversize {
+                size: 256,
+                max: 255
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_oversize_headers_before_the_payload() {
+        assert_eq!(
+            decode::<Small>(&[5, 0, 0, 0]).unwrap_err(),
+            FrameError::Oversize { size: 5, max: 4 }
+        );
+        assert!(decode::<Small>(&[4, 0, 0, 0]).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_fds_over_the_limits() {
+        assert_eq!(
+            LengthDelimited::<Bytes>::with_fds(Bytes::new(), vec![null_fd()]).unwrap_err(),
+            FrameError::FdsNotSupported
+        );
+
+        assert!(check_fds::<WithFds>(MAX_FDS as u64).is_ok());
+        assert_eq!(
+            check_fds::<WithFds>(MAX_FDS as u64 + 1),
+            Err(FrameError::TooManyFds { count: 254 })
+        );
+        assert_eq!(
+            decode::<WithFds>(b"RP\x00\xfe").unwrap_err(),
+            FrameError::TooManyFds { count: 254 }
+        );
+    }
+
+    #[test]
+    fn waits_for_the_fds_of_a_frame() {
+        let mut decoder = BufDecoder::new();
+        decoder.push_bytes(Bytes::from_static(b"RP\x01\x01x"));
+        assert!(LengthDelimited::<Bytes, WithFds>::decode(&mut decoder)
+            .unwrap()
+            .is_none());
+
+        decoder.push_fds([null_fd()]);
+        let frame = LengthDelimited::<Bytes, WithFds>::decode(&mut decoder)
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame.payload(), &b"x"[..]);
+        assert_eq!(frame.fds().len(), 1);
+        assert_eq!((decoder.remaining(), decoder.remaining_fds()), (0, 0));
+    }
+
+    #[test]
+    fn round_trips_fds() {
+        let (mut tx, mut rx) = UnixStream::pair().unwrap();
+        let frame = LengthDelimited::<_, WithFds>::with_fds(
+            Bytes::from_static(b"fds"),
+            vec![null_fd(), null_fd()],
+        )
+        .unwrap();
+        let mut encoder = BufEncoder::new(&mut tx);
+        frame.encode(&mut encoder);
+        encoder.into_result().unwrap();
+
+        let mut decoder = BufDecoder::new();
+        decoder.fill(&mut rx).unwrap();
+        let frame = LengthDelimited::<Bytes, WithFds>::decode(&mut decoder)
+            .unwrap()
+            .unwrap();
+        assert_eq!(frame.payload(), &b"fds"[..]);
+        assert_eq!(frame.fds().len(), 2);
+    }
+}
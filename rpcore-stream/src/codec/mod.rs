@@ -2,6 +2,28 @@ use std::os::fd::{AsFd, OwnedFd};
 
 use bytes::{Buf, Bytes};
 
+mod decoder;
+pub use decoder::BufDecoder;
+
+mod encoder;
+pub use encoder::BufEncoder;
+
+mod length_delimited;
+pub use length_delimited::{
+    DefaultFormat, Endian, FrameError, FrameFormat, LengthDelimited, PrefixWidth,
+};
+
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "bincode")]
+pub use self::serde::Bincode;
+#[cfg(feature = "json")]
+pub use self::serde::Json;
+#[cfg(feature = "postcard")]
+pub use self::serde::Postcard;
+#[cfg(feature = "serde")]
+pub use self::serde::{Serde, SerdeError, SerdeFormat, SerdeHandler, SerdeLayer};
+
 /// A trait for types that can be encoded into a stream.
 pub trait Encode {
     /// Encodes `self` into the given `Encoder`.
@@ -40,6 +62,17 @@ pub trait Decode: Sized {
     /// - `Ok(None)`: More data is needed to decode a complete message.
     /// - `Err(Self::Err)`: A decoding error occurred.
     fn decode<D: Decoder>(decoder: D) -> Result<Option<Self>, Self::Err>;
+
+    /// Returns whether decoding failed with `err` after consuming the whole
+    /// message, so that the message can be skipped and decoding can go on with
+    /// the next one.
+    ///
+    /// By default, errors are not skippable: the stream cannot be
+    /// resynchronized.
+    #[allow(unused_variables)]
+    fn is_skippable(err: &Self::Err) -> bool {
+        false
+    }
 }
 
 /// A decoder that reads from an internal, potentially non-contiguous buffer.
@@ -69,3 +102,29 @@ pub trait Decoder {
     /// Reads exactly `count` file descriptors from the buffer into `buf`.
     fn read_fds(&mut self, buf: &mut Vec<OwnedFd>, count: usize);
 }
+
+impl<D: Decoder + ?Sized> Decoder for &mut D {
+    fn peak(&self, offset: usize, count: usize) -> Bytes {
+        (**self).peak(offset, count)
+    }
+
+    fn advance(&mut self, count: usize) {
+        (**self).advance(count)
+    }
+
+    fn remaining(&self) -> usize {
+        (**self).remaining()
+    }
+
+    fn read(&mut self, buf: &mut Vec<Bytes>, count: usize) {
+        (**self).read(buf, count)
+    }
+
+    fn remaining_fds(&self) -> usize {
+        (**self).remaining_fds()
+    }
+
+    fn read_fds(&mut self, buf: &mut Vec<OwnedFd>, count: usize) {
+        (**self).read_fds(buf, count)
+    }
+}
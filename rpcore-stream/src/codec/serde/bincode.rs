@@ -0,0 +1,22 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::SerdeFormat;
+
+/// Bincode with its standard configuration.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bincode;
+
+impl SerdeFormat for Bincode {
+    type EncodeErr = bincode::error::EncodeError;
+    type DecodeErr = bincode::error::DecodeError;
+
+    fn serialize<T: Serialize>(value: &T) -> Result<Vec<u8>, Self::EncodeErr> {
+        bincode::serde::encode_to_vec(value, bincode::config::standard())
+    }
+
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Self::DecodeErr> {
+        bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+            .map(|(value, _)| value)
+    }
+}
@@ -0,0 +1,21 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::SerdeFormat;
+
+/// JSON, with `serde_json`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+impl SerdeFormat for Json {
+    type EncodeErr = serde_json::Error;
+    type DecodeErr = serde_json::Error;
+
+    fn serialize<T: Serialize>(value: &T) -> Result<Vec<u8>, Self::EncodeErr> {
+        serde_json::to_vec(value)
+    }
+
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Self::DecodeErr> {
+        serde_json::from_slice(bytes)
+    }
+}
@@ -0,0 +1,243 @@
+#[cfg(feature = "bincode")]
+mod bincode;
+#[cfg(feature = "bincode")]
+pub use self::bincode::Bincode;
+
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json")]
+pub use self::json::Json;
+
+#[cfg(feature = "postcard")]
+mod postcard;
+#[cfg(feature = "postcard")]
+pub use self::postcard::Postcard;
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use ::serde::de::DeserializeOwned;
+use ::serde::Serialize;
+use bytes::Bytes;
+use rpcore_core::layer::Layer;
+use rpcore_core::{callback_fn, Callback, Handler};
+
+use crate::codec::{
+    Decode, Decoder, DefaultFormat, Encode, Encoder, FrameError, FrameFormat, LengthDelimited,
+};
+
+/// A serde data format, such as [`Json`] or [`Bincode`].
+pub trait SerdeFormat {
+    type EncodeErr: fmt::Display;
+    type DecodeErr: std::error::Error;
+
+    fn serialize<T: Serialize>(value: &T) -> Result<Vec<u8>, Self::EncodeErr>;
+
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Self::DecodeErr>;
+}
+
+/// A value that is encoded with the serde format `S`, into frames described by
+/// `F`.
+///
+/// A value that cannot be serialized, or whose serialization exceeds the
+/// maximum frame size, is logged and left unfinished, which
+/// [`BufEncoder::into_result`](crate::codec::BufEncoder::into_result) reports
+/// as an error. A frame whose payload cannot be deserialized is skipped, see
+/// [`Decode::is_skippable`]. File descriptors are not supported.
+pub struct Serde<T, S, F = DefaultFormat> {
+    pub inner: T,
+    _format: PhantomData<fn() -> (S, F)>,
+}
+
+impl<T, S, F> Serde<T, S, F> {
+    pub const fn new(inner: T) -> Self {
+        Self {
+            inner,
+            _format: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T, S, F> From<T> for Serde<T, S, F> {
+    fn from(inner: T) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<T: fmt::Debug, S, F> fmt::Debug for Serde<T, S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Serde").field(&self.inner).finish()
+    }
+}
+
+impl<T, S, F> Encode for Serde<T, S, F>
+where
+    T: Serialize,
+    S: SerdeFormat,
+    F: FrameFormat,
+{
+    fn encode<C: Encoder>(self, encoder: C) {
+        let bytes = match S::serialize(&self.inner) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                log::error!("Failed to serialize {}: {e}", std::any::type_name::<T>());
+                return;
+            }
+        };
+
+        match LengthDelimited::<_, F>::new(bytes) {
+            Ok(frame) => frame.encode(encoder),
+            Err(e) => log::error!("Failed to frame {}: {e}", std::any::type_name::<T>()),
+        }
+    }
+}
+
+impl<T, S, F> Decode for Serde<T, S, F>
+where
+    T: DeserializeOwned,
+    S: SerdeFormat,
+    F: FrameFormat,
+{
+    type Err = SerdeError<S::DecodeErr>;
+
+    fn decode<D: Decoder>(decoder: D) -> Result<Option<Self>, Self::Err> {
+        let Some(frame) = LengthDelimited::<Bytes, F>::decode(decoder)? else {
+            return Ok(None);
+        };
+
+        let (payload, _) = frame.into_parts();
+        S::deserialize(&payload)
+            .map(|inner| Some(Self::new(inner)))
+            .map_err(SerdeError::Deserialize)
+    }
+
+    /// The whole frame is consumed before it is deserialized.
+    fn is_skippable(err: &Self::Err) -> bool {
+        matches!(err, SerdeError::Deserialize(_))
+    }
+}
+
+#[derive(Debug)]
+pub enum SerdeError<E> {
+    Frame(FrameError),
+    Deserialize(E),
+}
+
+impl<E> From<FrameError> for SerdeError<E> {
+    fn from(e: FrameError) -> Self {
+        SerdeError::Frame(e)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for SerdeError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerdeError::Frame(e) => write!(f, "frame error: {e}"),
+            SerdeError::Deserialize(e) => write!(f, "deserialize error: {e}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for SerdeError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SerdeError::Frame(e) => Some(e),
+            SerdeError::Deserialize(e) => Some(e),
+        }
+    }
+}
+
+/// A handler that serves `Serde<Arg, S, F>` with an inner `Handler<Arg>`,
+/// replying with `Serde<H::Ret, S, F>`.
+pub struct SerdeHandler<H, S, F = DefaultFormat> {
+    inner: H,
+    _format: PhantomData<fn() -> (S, F)>,
+}
+
+impl<H, S, F> SerdeHandler<H, S, F> {
+    pub const fn new(inner: H) -> Self {
+        Self {
+            inner,
+            _format: PhantomData,
+        }
+    }
+
+    pub fn get_ref(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut H {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+}
+
+impl<H: fmt::Debug, S, F> fmt::Debug for SerdeHandler<H, S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SerdeHandler")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<H, Arg, S, F> Handler<Serde<Arg, S, F>> for SerdeHandler<H, S, F>
+where
+    H: Handler<Arg>,
+    H::Ret: 'static,
+    S: 'static,
+    F: 'static,
+{
+    type Ret = Serde<H::Ret, S, F>;
+
+    fn handle(&mut self, arg: Serde<Arg, S, F>, callback: impl Callback<Ret = Self::Ret>) {
+        self.inner.handle(
+            arg.inner,
+            callback_fn(move |ret| callback.call(Serde::new(ret))),
+        );
+    }
+}
+
+pub struct SerdeLayer<S, F = DefaultFormat> {
+    _format: PhantomData<fn() -> (S, F)>,
+}
+
+impl<S, F> SerdeLayer<S, F> {
+    pub const fn new() -> Self {
+        Self {
+            _format: PhantomData,
+        }
+    }
+}
+
+impl<S, F> Default for SerdeLayer<S, F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, F> Clone for SerdeLayer<S, F> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+impl<S, F> fmt::Debug for SerdeLayer<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SerdeLayer").finish()
+    }
+}
+
+impl<H, S, F> Layer<H> for SerdeLayer<S, F> {
+    type Handler = SerdeHandler<H, S, F>;
+
+    fn layer(&self, inner: H) -> Self::Handler {
+        SerdeHandler::new(inner)
+    }
+}
@@ -0,0 +1,21 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::SerdeFormat;
+
+/// Postcard, a compact format suited to constrained peers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Postcard;
+
+impl SerdeFormat for Postcard {
+    type EncodeErr = postcard::Error;
+    type DecodeErr = postcard::Error;
+
+    fn serialize<T: Serialize>(value: &T) -> Result<Vec<u8>, Self::EncodeErr> {
+        postcard::to_allocvec(value)
+    }
+
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Self::DecodeErr> {
+        postcard::from_bytes(bytes)
+    }
+}
@@ -0,0 +1,292 @@
+use std::os::fd::OwnedFd;
+
+use bytes::{Buf, Bytes};
+use rpcore_core::invocation_source::readiness::EventSource;
+use rpcore_core::invocation_source::recv::{RecvInvocation, TryRecvInvocation};
+use rpcore_core::{Callback, Invocation};
+
+use crate::codec::{Decode, Decoder, Encode, Encoder};
+
+/// The size of the request id that prefixes a [`Correlated`] message.
+const ID_SIZE: usize = 8;
+
+/// An envelope that tags a message with a request id.
+///
+/// Wire format: `id: u64 LE`, followed by the encoded `inner` message.
+///
+/// A client that pipelines several calls on one connection tags each request
+/// with a distinct id, and the server replies with the id of the request
+/// being answered, so that replies may complete out of order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Correlated<T> {
+    pub id: u64,
+    pub inner: T,
+}
+
+impl<T> Correlated<T> {
+    pub const fn new(id: u64, inner: T) -> Self {
+        Self { id, inner }
+    }
+}
+
+impl<T: Encode> Encode for Correlated<T> {
+    fn encode<C: Encoder>(self, mut encoder: C) {
+        encoder.write_bytes(&self.id.to_le_bytes()[..]);
+        self.inner.encode(encoder);
+    }
+}
+
+impl<T: Decode> Decode for Correlated<T> {
+    type Err = T::Err;
+
+    fn decode<D: Decoder>(mut decoder: D) -> Result<Option<Self>, Self::Err> {
+        let Some(id) = peek_id(&decoder) else {
+            return Ok(None);
+        };
+
+        let mut body = Skip {
+            inner: &mut decoder,
+            skip: ID_SIZE,
+        };
+        let Some(inner) = T::decode(&mut body)? else {
+            return Ok(None);
+        };
+        body.consume_skipped();
+
+        Ok(Some(Self { id, inner }))
+    }
+
+    /// The id is consumed along with the first bytes of the message, so it is
+    /// skipped along with it.
+    fn is_skippable(err: &Self::Err) -> bool {
+        T::is_skippable(err)
+    }
+}
+
+/// Returns the id of the next [`Correlated`] message in `decoder`, once it has
+/// arrived.
+pub(crate) fn peek_id<D: Decoder + ?Sized>(decoder: &D) -> Option<u64> {
+    (decoder.remaining() >= ID_SIZE).then(|| decoder.peak(0, ID_SIZE).get_u64_le())
+}
+
+/// A decoder that hides the first `skip` bytes of `inner`, which are only
+/// consumed along with the first bytes consumed through it.
+struct Skip<'a, D> {
+    inner: &'a mut D,
+    skip: usize,
+}
+
+impl<D: Decoder> Skip<'_, D> {
+    fn consume_skipped(&mut self) {
+        if self.skip > 0 {
+            self.inner.advance(self.skip);
+            self.skip = 0;
+        }
+    }
+}
+
+impl<D: Decoder> Decoder for Skip<'_, D> {
+    fn peak(&self, offset: usize, count: usize) -> Bytes {
+        self.inner.peak(self.skip + offset, count)
+    }
+
+    fn advance(&mut self, count: usize) {
+        self.consume_skipped();
+        self.inner.advance(count);
+    }
+
+    fn remaining(&self) -> usize {
+        self.inner.remaining() - self.skip
+    }
+
+    fn read(&mut self, buf: &mut Vec<Bytes>, count: usize) {
+        self.consume_skipped();
+        self.inner.read(buf, count);
+    }
+
+    fn remaining_fds(&self) -> usize {
+        self.inner.remaining_fds()
+    }
+
+    fn read_fds(&mut self, buf: &mut Vec<OwnedFd>, count: usize) {
+        self.inner.read_fds(buf, count);
+    }
+}
+
+/// A callback that tags the return value with the id of the request it
+/// answers.
+#[derive(Debug)]
+pub struct CorrelatedCallback<Cb> {
+    id: u64,
+    inner: Cb,
+}
+
+impl<Cb> CorrelatedCallback<Cb> {
+    pub const fn new(id: u64, inner: Cb) -> Self {
+        Self { id, inner }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl<Cb, Ret> Callback for CorrelatedCallback<Cb>
+where
+    Cb: Callback<Ret = Correlated<Ret>>,
+{
+    type Ret = Ret;
+
+    fn call(self, out: Self::Ret) {
+        self.inner.call(Correlated::new(self.id, out));
+    }
+}
+
+/// An invocation source that unwraps [`Correlated`] arguments, and hands out
+/// [`CorrelatedCallback`]s that tag replies with the same id.
+///
+/// Handlers see plain arguments and return plain values, yet may complete
+/// calls in any order, e.g. behind a `ConcurrencyLimit` that hands calls to
+/// other threads.
+#[derive(Debug)]
+pub struct CorrelatedSource<I> {
+    inner: I,
+}
+
+impl<I> CorrelatedSource<I> {
+    pub const fn new(inner: I) -> Self {
+        Self { inner }
+    }
+
+    pub fn get_ref(&self) -> &I {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+fn unwrap_invocation<Arg, Cb>(
+    inv: Invocation<Correlated<Arg>, Cb>,
+) -> Invocation<Arg, CorrelatedCallback<Cb>> {
+    Invocation {
+        arg: inv.arg.inner,
+        callback: CorrelatedCallback::new(inv.arg.id, inv.callback),
+    }
+}
+
+impl<I, Arg, Cb> RecvInvocation<Arg, CorrelatedCallback<Cb>> for CorrelatedSource<I>
+where
+    I: RecvInvocation<Correlated<Arg>, Cb>,
+{
+    type RecvErr = I::RecvErr;
+
+    fn recv(&mut self) -> Result<Invocation<Arg, CorrelatedCallback<Cb>>, Self::RecvErr> {
+        self.inner.recv().map(unwrap_invocation)
+    }
+}
+
+impl<I, Arg, Cb> TryRecvInvocation<Arg, CorrelatedCallback<Cb>> for CorrelatedSource<I>
+where
+    I: TryRecvInvocation<Correlated<Arg>, Cb>,
+{
+    type TryRecvErr = I::TryRecvErr;
+
+    fn try_recv(&mut self) -> Result<Invocation<Arg, CorrelatedCallback<Cb>>, Self::TryRecvErr> {
+        self.inner.try_recv().map(unwrap_invocation)
+    }
+}
+
+impl<I, Reactor> EventSource<Reactor> for CorrelatedSource<I>
+where
+    I: EventSource<Reactor>,
+{
+    type Token = I::Token;
+    type Err = I::Err;
+
+    fn register(&mut self, registry: &mut Reactor, token: Self::Token) -> Result<(), Self::Err> {
+        self.inner.register(registry, token)
+    }
+
+    fn deregister(&mut self, registry: &mut Reactor) -> Result<(), Self::Err> {
+        self.inner.deregister(registry)
+    }
+
+    fn on_ready(&mut self) -> Result<(), Self::Err> {
+        self.inner.on_ready()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::net::UnixStream;
+
+    use super::*;
+    use crate::codec::{BufDecoder, BufEncoder, LengthDelimited};
+
+    type Frame = Correlated<LengthDelimited<Bytes>>;
+
+    fn frame(id: u64, payload: &'static [u8]) -> Frame {
+        Correlated::new(
+            id,
+            LengthDelimited::new(Bytes::from_static(payload)).unwrap(),
+        )
+    }
+
+    /// Returns the bytes of `frames` as sent on a stream.
+    fn encode(frames: impl IntoIterator<Item = Frame>) -> Bytes {
+        let (mut tx, mut rx) = UnixStream::pair().unwrap();
+        for frame in frames {
+            let mut encoder = BufEncoder::new(&mut tx);
+            frame.encode(&mut encoder);
+            encoder.into_result().unwrap();
+        }
+        drop(tx);
+
+        let mut decoder = BufDecoder::new();
+        while decoder.fill(&mut rx).unwrap() > 0 {}
+        let mut chunks = Vec::new();
+        decoder.read(&mut chunks, decoder.remaining());
+        chunks.concat().into()
+    }
+
+    #[test]
+    fn round_trips_pipelined_ids() {
+        let bytes = encode([frame(7, b"a"), frame(u64::MAX, b"bc")]);
+        assert_eq!(bytes[..ID_SIZE], 7u64.to_le_bytes());
+
+        let mut decoder = BufDecoder::new();
+        decoder.push_bytes(bytes);
+        assert_eq!(peek_id(&decoder), Some(7));
+
+        let first = Frame::decode(&mut decoder).unwrap().unwrap();
+        assert_eq!(first.id, 7);
+        assert_eq!(first.inner.payload(), &b"a"[..]);
+        assert_eq!(peek_id(&decoder), Some(u64::MAX));
+
+        let second = Frame::decode(&mut decoder).unwrap().unwrap();
+        assert_eq!(second.id, u64::MAX);
+        assert_eq!(second.inner.payload(), &b"bc"[..]);
+        assert_eq!(decoder.remaining(), 0);
+    }
+
+    #[test]
+    fn partial_message_is_left_in_the_decoder() {
+        let bytes = encode([frame(42, b"hello")]);
+
+        let mut decoder = BufDecoder::new();
+        for i in 0..bytes.len() - 1 {
+            decoder.push_bytes(bytes.slice(i..i + 1));
+            assert!(Frame::decode(&mut decoder).unwrap().is_none());
+            // Not even the id is consumed.
+            assert_eq!(decoder.remaining(), i + 1);
+        }
+
+        decoder.push_bytes(bytes.slice(bytes.len() - 1..));
+        let frame = Frame::decode(&mut decoder).unwrap().unwrap();
+        assert_eq!(frame.id, 42);
+        assert_eq!(frame.inner.payload(), &b"hello"[..]);
+        assert_eq!(decoder.remaining(), 0);
+    }
+}
@@ -1,6 +1,6 @@
 use std::cell::RefCell;
 use std::io::{self, IoSlice, IoSliceMut};
-use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::fd::{AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
 use std::{mem, ptr, slice};
 
 use libc::{self, ssize_t};
@@ -82,7 +82,7 @@ thread_local! {
 /// A return value of -1 indicates an error, in which case `io::Error::last_os_error`
 /// is used to retrieve the error information. Otherwise, the return value is
 /// converted to a `usize`.
-fn cvt(ret: ssize_t) -> io::Result<usize> {
+pub(crate) fn cvt(ret: ssize_t) -> io::Result<usize> {
     if ret == -1 {
         Err(io::Error::last_os_error())
     } else {
@@ -90,6 +90,24 @@ fn cvt(ret: ssize_t) -> io::Result<usize> {
     }
 }
 
+/// Blocks until `fd` is ready for any of the poll `events`, e.g. when a
+/// non-blocking operation on it would block.
+pub(crate) fn wait_fd(fd: BorrowedFd<'_>, events: libc::c_short) -> io::Result<()> {
+    let mut pollfd = libc::pollfd {
+        fd: fd.as_raw_fd(),
+        events,
+        revents: 0,
+    };
+    loop {
+        // SAFETY: Safe to call `poll` with a single valid pollfd.
+        match cvt(unsafe { libc::poll(&mut pollfd, 1, -1) } as ssize_t) {
+            Ok(_) => return Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 fn read_vectored_with_fds(
     this_fd: RawFd,
     bufs: &mut [IoSliceMut<'_>],
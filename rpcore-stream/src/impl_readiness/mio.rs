@@ -0,0 +1,55 @@
+use std::io;
+use std::os::fd::{AsFd, AsRawFd};
+
+use mio::unix::SourceFd;
+use rpcore_core::invocation_source::readiness;
+
+use crate::{Acceptor, StreamSource};
+
+impl<Arg, Ret, R, W> readiness::EventSource<mio::Registry> for StreamSource<Arg, Ret, R, W>
+where
+    R: AsFd,
+{
+    type Token = mio::Token;
+    type Err = io::Error;
+
+    fn register(
+        &mut self,
+        registry: &mut mio::Registry,
+        token: Self::Token,
+    ) -> Result<(), Self::Err> {
+        registry.register(
+            &mut SourceFd(&self.reader.as_fd().as_raw_fd()),
+            token,
+            mio::Interest::READABLE,
+        )
+    }
+
+    fn deregister(&mut self, registry: &mut mio::Registry) -> Result<(), Self::Err> {
+        registry.deregister(&mut SourceFd(&self.reader.as_fd().as_raw_fd()))
+    }
+}
+
+impl<L, F, A> readiness::EventSource<mio::Registry> for Acceptor<L, F, A>
+where
+    L: AsFd,
+{
+    type Token = mio::Token;
+    type Err = io::Error;
+
+    fn register(
+        &mut self,
+        registry: &mut mio::Registry,
+        token: Self::Token,
+    ) -> Result<(), Self::Err> {
+        registry.register(
+            &mut SourceFd(&self.listener.as_fd().as_raw_fd()),
+            token,
+            mio::Interest::READABLE,
+        )
+    }
+
+    fn deregister(&mut self, registry: &mut mio::Registry) -> Result<(), Self::Err> {
+        registry.deregister(&mut SourceFd(&self.listener.as_fd().as_raw_fd()))
+    }
+}
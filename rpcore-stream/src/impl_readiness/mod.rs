@@ -0,0 +1,4 @@
+#[cfg(feature = "mio")]
+mod mio;
+#[cfg(feature = "nix")]
+mod nix;
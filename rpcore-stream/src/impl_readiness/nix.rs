@@ -0,0 +1,42 @@
+use std::os::fd::AsFd;
+
+use nix::sys::epoll::{Epoll, EpollEvent, EpollFlags};
+use rpcore_core::invocation_source::readiness;
+
+use crate::{Acceptor, StreamSource};
+
+impl<Arg, Ret, R, W> readiness::EventSource<Epoll> for StreamSource<Arg, Ret, R, W>
+where
+    R: AsFd,
+{
+    type Token = u64;
+    type Err = nix::Error;
+
+    fn register(&mut self, registry: &mut Epoll, token: Self::Token) -> Result<(), Self::Err> {
+        let flags = EpollFlags::EPOLLET | EpollFlags::EPOLLIN;
+        let event = EpollEvent::new(flags, token);
+        registry.add(self.reader.as_fd(), event)
+    }
+
+    fn deregister(&mut self, registry: &mut Epoll) -> Result<(), Self::Err> {
+        registry.delete(self.reader.as_fd())
+    }
+}
+
+impl<L, F, A> readiness::EventSource<Epoll> for Acceptor<L, F, A>
+where
+    L: AsFd,
+{
+    type Token = u64;
+    type Err = nix::Error;
+
+    fn register(&mut self, registry: &mut Epoll, token: Self::Token) -> Result<(), Self::Err> {
+        let flags = EpollFlags::EPOLLET | EpollFlags::EPOLLIN;
+        let event = EpollEvent::new(flags, token);
+        registry.add(self.listener.as_fd(), event)
+    }
+
+    fn deregister(&mut self, registry: &mut Epoll) -> Result<(), Self::Err> {
+        registry.delete(self.listener.as_fd())
+    }
+}
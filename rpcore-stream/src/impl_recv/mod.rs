@@ -0,0 +1,95 @@
+mod recv_error;
+use std::io;
+use std::os::fd::AsFd;
+
+pub use recv_error::RecvError;
+use rpcore_core::invocation_source::recv;
+
+use crate::codec::{Decode, Encode};
+use crate::{extended_io, Invocation, StreamCallback, StreamSource};
+
+impl<Arg, Ret, R, W> StreamSource<Arg, Ret, R, W>
+where
+    Arg: Decode,
+    R: extended_io::Read + AsFd,
+{
+    fn recv_inner(
+        &mut self,
+        blocking: bool,
+    ) -> Result<Invocation<Arg, Ret, W>, RecvError<Arg::Err>> {
+        if self.closed {
+            return Err(RecvError::Closed);
+        }
+
+        loop {
+            match Arg::decode(&mut self.decoder) {
+                Ok(Some(arg)) => {
+                    return Ok(Invocation {
+                        arg,
+                        callback: self.callback(),
+                    })
+                }
+                Ok(None) => {}
+                Err(e) if Arg::is_skippable(&e) => return Err(RecvError::Skipped(e)),
+                Err(e) => {
+                    self.closed = true;
+                    return Err(RecvError::Decode(e));
+                }
+            }
+
+            match self.decoder.fill(&mut self.reader) {
+                Ok(0) => {
+                    self.closed = true;
+                    return Err(RecvError::Closed);
+                }
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    if !blocking {
+                        return Err(RecvError::Empty);
+                    }
+                    if let Err(e) = extended_io::wait_fd(self.reader.as_fd(), libc::POLLIN) {
+                        self.closed = true;
+                        return Err(RecvError::Io(e));
+                    }
+                }
+                Err(e) => {
+                    self.closed = true;
+                    return Err(RecvError::Io(e));
+                }
+            }
+        }
+    }
+}
+
+impl<Arg, Ret, R, W> recv::RecvInvocation<Arg, StreamCallback<Ret, W>>
+    for StreamSource<Arg, Ret, R, W>
+where
+    Arg: Decode,
+    Arg::Err: std::error::Error + 'static,
+    Ret: Encode + 'static,
+    R: extended_io::Read + AsFd,
+    W: extended_io::Write + AsFd + Send + 'static,
+{
+    type RecvErr = RecvError<Arg::Err>;
+
+    fn recv(&mut self) -> Result<Invocation<Arg, Ret, W>, Self::RecvErr> {
+        self.recv_inner(true)
+    }
+}
+
+impl<Arg, Ret, R, W> recv::TryRecvInvocation<Arg, StreamCallback<Ret, W>>
+    for StreamSource<Arg, Ret, R, W>
+where
+    Arg: Decode,
+    Arg::Err: std::error::Error + 'static,
+    Ret: Encode + 'static,
+    R: extended_io::Read + AsFd,
+    W: extended_io::Write + AsFd + Send + 'static,
+{
+    type TryRecvErr = RecvError<Arg::Err>;
+
+    fn try_recv(&mut self) -> Result<Invocation<Arg, Ret, W>, Self::TryRecvErr> {
+        self.recv_inner(false)
+    }
+}
@@ -0,0 +1,60 @@
+use std::{fmt, io};
+
+use rpcore_core::invocation_source::recv;
+
+/// An error returned when receiving from a
+/// [`StreamSource`](crate::StreamSource).
+///
+/// Except for `Empty` and `Skipped`, all errors are fatal: the stream can not
+/// be resynchronized and every following receive returns `Closed`.
+#[derive(Debug)]
+pub enum RecvError<E> {
+    /// No complete message is available yet.
+    Empty,
+    /// The peer closed the stream.
+    Closed,
+    Io(io::Error),
+    Decode(E),
+    /// A message failed to decode and was skipped, see
+    /// [`Decode::is_skippable`](crate::codec::Decode::is_skippable). It is not
+    /// replied to.
+    Skipped(E),
+}
+
+impl<E: fmt::Display> fmt::Display for RecvError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("receiving on an empty stream"),
+            Self::Closed => f.write_str("receiving on a closed stream"),
+            Self::Io(e) => write!(f, "failed to read from stream: {e}"),
+            Self::Decode(e) => write!(f, "failed to decode message: {e}"),
+            Self::Skipped(e) => write!(f, "skipped a message that failed to decode: {e}"),
+        }
+    }
+}
+
+impl<E> std::error::Error for RecvError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Decode(e) | Self::Skipped(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl<E> recv::Error for RecvError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn is_closed(&self) -> bool {
+        matches!(self, Self::Closed | Self::Io(_) | Self::Decode(_))
+    }
+
+    fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+}
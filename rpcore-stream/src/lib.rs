@@ -2,5 +2,30 @@ pub mod buf;
 pub mod codec;
 pub mod extended_io;
 
+#[cfg(feature = "socket2")]
 pub mod split;
+#[cfg(feature = "socket2")]
 pub use split::split;
+
+mod stream_source;
+pub use stream_source::StreamSource;
+
+mod stream_callback;
+pub use stream_callback::StreamCallback;
+
+#[cfg(feature = "client")]
+pub mod stream_client;
+
+mod correlated;
+pub use correlated::{Correlated, CorrelatedCallback, CorrelatedSource};
+
+mod acceptor;
+pub use acceptor::{Acceptor, Listener};
+
+mod impl_recv;
+pub use impl_recv::RecvError;
+
+mod impl_readiness;
+
+pub type Invocation<Arg, Ret, W = std::os::unix::net::UnixStream> =
+    rpcore_core::Invocation<Arg, StreamCallback<Ret, W>>;
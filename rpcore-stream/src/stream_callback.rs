@@ -0,0 +1,61 @@
+use std::any::type_name;
+use std::fmt;
+use std::marker::PhantomData;
+use std::os::fd::{AsFd, AsRawFd};
+use std::sync::{Arc, Mutex};
+
+use rpcore_core::Callback;
+
+use crate::codec::{BufEncoder, Encode};
+use crate::extended_io;
+
+/// A callback that encodes the return value onto the write half of a stream.
+///
+/// If the value fails to encode or to be written, the write half is shut
+/// down: the stream may be left in the middle of a message, and the peer
+/// would otherwise wait for a reply that never comes.
+pub struct StreamCallback<T, W> {
+    writer: Arc<Mutex<W>>,
+    _phantom: PhantomData<fn(T)>,
+}
+
+impl<T, W> StreamCallback<T, W> {
+    pub fn new(writer: Arc<Mutex<W>>) -> Self {
+        Self {
+            writer,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, W> Callback for StreamCallback<T, W>
+where
+    T: Encode + 'static,
+    W: extended_io::Write + AsFd + Send + 'static,
+{
+    type Ret = T;
+
+    fn call(self, val: T) {
+        // The writer is locked for the whole message, so that replies from
+        // different threads are not interleaved.
+        let mut writer = self
+            .writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let mut encoder = BufEncoder::new(&mut *writer);
+        val.encode(&mut encoder);
+        if let Err(e) = encoder.into_result() {
+            log::warn!("Failed to send {}, shutting down: {e}", type_name::<T>());
+            // SAFETY: Safe to call `shutdown` with a valid fd.
+            unsafe { libc::shutdown(writer.as_fd().as_raw_fd(), libc::SHUT_WR) };
+        }
+    }
+}
+
+impl<T, W> fmt::Debug for StreamCallback<T, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(format!("StreamCallback<{}>", type_name::<T>()).as_str())
+            .finish()
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+use std::net::TcpStream;
+use std::os::fd::{AsFd, AsRawFd, OwnedFd};
+use std::os::unix::net::UnixStream;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use oneshot::RecvTimeoutError;
+use rpcore_core::Call;
+
+#[cfg(feature = "serde")]
+use crate::codec::Serde;
+use crate::codec::{BufDecoder, BufEncoder, Decode, Encode};
+use crate::correlated::{self, Correlated};
+use crate::extended_io;
+use crate::stream_client::{Error, Result};
+
+/// A blocking client that calls a stream server.
+///
+/// Every call is sent as a [`Correlated`] message, so the server must serve a
+/// [`CorrelatedSource`](crate::CorrelatedSource). Replies are received by a
+/// background thread and matched to their callers by id, so a client can be
+/// cloned and shared by many concurrent callers over one connection.
+///
+/// The connection is shut down once all clones are dropped.
+///
+/// As with an `MpscClient`, a call fails with [`Error::ServerClosed`] if the
+/// connection is already closed when it is made, and with
+/// [`Error::ServerInternalError`] if the connection closes before its reply
+/// arrives, or if its reply cannot be decoded.
+///
+/// Unlike an `MpscClient`, the client cannot tell when the server drops the
+/// callback of a single call while keeping the connection open: [`call`] then
+/// waits until the connection closes. Use [`call_timeout`] to bound the wait.
+///
+/// [`call`]: StreamClient::call
+/// [`call_timeout`]: StreamClient::call_timeout
+pub struct StreamClient<Arg, Ret, W = UnixStream> {
+    shared: Arc<Shared<Ret, W>>,
+    _phantom: PhantomData<fn(Arg)>,
+}
+
+struct Shared<Ret, W> {
+    writer: Mutex<W>,
+    /// A duplicate of the writer's fd, used to shut down the connection.
+    fd: OwnedFd,
+    next_id: AtomicU64,
+    pending: Arc<Mutex<Pending<Ret>>>,
+}
+
+struct Pending<Ret> {
+    calls: HashMap<u64, oneshot::Sender<Result<Ret>>>,
+    /// Set once the connection can no longer deliver replies.
+    closed: Option<Closed>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Closed {
+    /// The server closed the connection, or reading from it failed.
+    Server,
+    /// The server sent a reply that could not be decoded nor skipped.
+    Internal,
+}
+
+impl<Ret> Pending<Ret> {
+    /// Fails the calls still waiting for a reply, which the server can no
+    /// longer send.
+    fn close(&mut self, reason: Closed) {
+        self.closed.get_or_insert(reason);
+        for (_, tx) in self.calls.drain() {
+            drop(tx.send(Err(Error::ServerInternalError)));
+        }
+    }
+
+    fn error(&self) -> Error {
+        match self.closed {
+            Some(Closed::Internal) => Error::ServerInternalError,
+            _ => Error::ServerClosed,
+        }
+    }
+}
+
+impl<Arg, Ret> StreamClient<Arg, Ret>
+where
+    Ret: Decode + Send + 'static,
+    Ret::Err: fmt::Display,
+{
+    /// Creates a new `StreamClient` that calls over `stream`, which must be in
+    /// blocking mode.
+    pub fn from_unix_stream(stream: UnixStream) -> io::Result<Self> {
+        let reader = stream.try_clone()?;
+        Self::new(reader, stream)
+    }
+}
+
+impl<Arg, Ret> StreamClient<Arg, Ret, TcpStream>
+where
+    Ret: Decode + Send + 'static,
+    Ret::Err: fmt::Display,
+{
+    /// Creates a new `StreamClient` that calls over `stream`, which must be in
+    /// blocking mode.
+    pub fn from_tcp_stream(stream: TcpStream) -> io::Result<Self> {
+        let reader = stream.try_clone()?;
+        Self::new(reader, stream)
+    }
+}
+
+impl<Arg, Ret, W> StreamClient<Arg, Ret, W>
+where
+    Ret: Decode + Send + 'static,
+    Ret::Err: fmt::Display,
+    W: AsFd,
+{
+    /// Creates a new `StreamClient` that sends calls to `writer`, and spawns a
+    /// thread that receives replies from `reader`.
+    pub fn new<R>(reader: R, writer: W) -> io::Result<Self>
+    where
+        R: extended_io::Read + Send + 'static,
+    {
+        let fd = writer.as_fd().try_clone_to_owned()?;
+        let pending = Arc::new(Mutex::new(Pending {
+            calls: HashMap::new(),
+            closed: None,
+        }));
+
+        let cloned = Arc::clone(&pending);
+        thread::Builder::new()
+            .name("rpcore-stream-client".to_owned())
+            .spawn(move || receive_replies(reader, &cloned))?;
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                writer: Mutex::new(writer),
+                fd,
+                next_id: AtomicU64::new(0),
+                pending,
+            }),
+            _phantom: PhantomData,
+        })
+    }
+}
+
+fn receive_replies<R, Ret>(mut reader: R, pending: &Mutex<Pending<Ret>>)
+where
+    R: extended_io::Read,
+    Ret: Decode,
+    Ret::Err: fmt::Display,
+{
+    let mut decoder = BufDecoder::new();
+    let reason = loop {
+        // Peeked beforehand, so that a reply that is skipped only fails its
+        // own call.
+        let id = correlated::peek_id(&decoder);
+        match Correlated::<Ret>::decode(&mut decoder) {
+            Ok(Some(reply)) => {
+                complete(pending, reply.id, Ok(reply.inner));
+                continue;
+            }
+            Ok(None) => {}
+            Err(e) if Correlated::<Ret>::is_skippable(&e) => {
+                let id = id.expect("a skipped reply has an id");
+                log::error!("Failed to decode reply to call {id}: {e}");
+                complete(pending, id, Err(Error::ServerInternalError));
+                continue;
+            }
+            Err(e) => {
+                log::error!("Failed to decode reply: {e}");
+                break Closed::Internal;
+            }
+        }
+
+        match decoder.fill(&mut reader) {
+            Ok(0) => break Closed::Server,
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => {
+                log::error!("Failed to receive reply: {e}");
+                break Closed::Server;
+            }
+        }
+    };
+
+    lock(pending).close(reason);
+}
+
+fn complete<Ret>(pending: &Mutex<Pending<Ret>>, id: u64, result: Result<Ret>) {
+    let tx = lock(pending).calls.remove(&id);
+    match tx {
+        // The caller may have timed out in the meantime.
+        Some(tx) => drop(tx.send(result)),
+        None => log::debug!("Dropped reply to call {id}"),
+    }
+}
+
+impl<Arg, Ret, W> StreamClient<Arg, Ret, W>
+where
+    Arg: Encode,
+    W: extended_io::Write + AsFd,
+{
+    /// Sends `arg` and blocks until its reply arrives or the connection closes.
+    pub fn call(&self, arg: Arg) -> Result<Ret> {
+        let (_, rx) = self.send(arg)?;
+        rx.recv().map_err(|_| self.pending().error())?
+    }
+
+    pub fn call_timeout(&self, arg: Arg, timeout: Duration) -> Result<Ret> {
+        let (id, rx) = self.send(arg)?;
+        match rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                self.pending().calls.remove(&id);
+                Err(Error::ServerTimeout)
+            }
+            Err(_) => Err(self.pending().error()),
+        }
+    }
+
+    pub async fn call_async(&self, arg: Arg) -> Result<Ret> {
+        let (_, rx) = self.send(arg)?;
+        rx.await.map_err(|_| self.pending().error())?
+    }
+
+    fn send(&self, arg: Arg) -> Result<(u64, oneshot::Receiver<Result<Ret>>)> {
+        let id = self.shared.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut pending = self.pending();
+            if pending.closed.is_some() {
+                return Err(pending.error());
+            }
+            pending.calls.insert(id, tx);
+        }
+
+        let result = {
+            let mut writer = lock(&self.shared.writer);
+            let mut encoder = BufEncoder::new(&mut *writer);
+            Correlated::new(id, arg).encode(&mut encoder);
+            encoder.into_result()
+        };
+        if let Err(e) = result {
+            self.pending().calls.remove(&id);
+            return Err(e.into());
+        }
+        Ok((id, rx))
+    }
+
+    fn pending(&self) -> MutexGuard<'_, Pending<Ret>> {
+        lock(&self.shared.pending)
+    }
+}
+
+impl<Arg, Ret, W> Call<Arg> for StreamClient<Arg, Ret, W>
+where
+    Arg: Encode,
+    W: extended_io::Write + AsFd,
+{
+    type Ret = Ret;
+    type Err = Error;
+
+    fn call(&self, arg: Arg) -> Result<Ret> {
+        StreamClient::call(self, arg)
+    }
+}
+
+/// Calls with unwrapped values, so that a client of serde messages can be used
+/// wherever a [`Call`] of the plain types is expected.
+#[cfg(feature = "serde")]
+impl<Arg, Ret, S, F, W> Call<Arg> for StreamClient<Serde<Arg, S, F>, Serde<Ret, S, F>, W>
+where
+    Serde<Arg, S, F>: Encode,
+    W: extended_io::Write + AsFd,
+{
+    type Ret = Ret;
+    type Err = Error;
+
+    fn call(&self, arg: Arg) -> Result<Ret> {
+        StreamClient::call(self, Serde::new(arg)).map(Serde::into_inner)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl<Ret, W> Drop for Shared<Ret, W> {
+    fn drop(&mut self) {
+        // Wakes up the receiving thread, this has no effect if `W` is not a
+        // socket.
+        // SAFETY: Safe to call `shutdown` with a valid fd.
+        unsafe { libc::shutdown(self.fd.as_raw_fd(), libc::SHUT_RDWR) };
+    }
+}
+
+impl<Arg, Ret, W> Clone for StreamClient<Arg, Ret, W> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<Arg, Ret, W> fmt::Debug for StreamClient<Arg, Ret, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamClient").finish_non_exhaustive()
+    }
+}
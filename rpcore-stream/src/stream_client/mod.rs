@@ -0,0 +1,22 @@
+mod client;
+pub use client::StreamClient;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Server closed")]
+    ServerClosed,
+
+    #[error("Server internal error")]
+    ServerInternalError,
+
+    #[error("Server timeout")]
+    ServerTimeout,
+
+    #[error(transparent)]
+    UnexpectedResponse(#[from] rpcore_core::UnexpectedResponse),
+
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
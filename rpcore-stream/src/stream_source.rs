@@ -0,0 +1,70 @@
+use std::any::type_name;
+use std::io;
+use std::marker::PhantomData;
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::sync::{Arc, Mutex};
+
+use crate::codec::BufDecoder;
+use crate::StreamCallback;
+
+/// An invocation source that decodes `Arg`s from the read half of a stream,
+/// and replies by encoding `Ret`s onto the write half.
+///
+/// [`TryRecvInvocation`](rpcore_core::invocation_source::recv::TryRecvInvocation)
+/// only works as expected if the stream is in non-blocking mode.
+pub struct StreamSource<Arg, Ret, R = UnixStream, W = UnixStream> {
+    pub(crate) reader: R,
+    pub(crate) writer: Arc<Mutex<W>>,
+    pub(crate) decoder: BufDecoder,
+    pub(crate) closed: bool,
+    _phantom: PhantomData<fn(Ret) -> Arg>,
+}
+
+impl<Arg, Ret> StreamSource<Arg, Ret> {
+    /// Creates a new `StreamSource` that reads from and writes to `stream`.
+    pub fn from_unix_stream(stream: UnixStream) -> io::Result<Self> {
+        let writer = stream.try_clone()?;
+        Ok(Self::new(stream, writer))
+    }
+}
+
+impl<Arg, Ret> StreamSource<Arg, Ret, TcpStream, TcpStream> {
+    /// Creates a new `StreamSource` that reads from and writes to `stream`.
+    pub fn from_tcp_stream(stream: TcpStream) -> io::Result<Self> {
+        let writer = stream.try_clone()?;
+        Ok(Self::new(stream, writer))
+    }
+}
+
+impl<Arg, Ret, R, W> StreamSource<Arg, Ret, R, W> {
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader,
+            writer: Arc::new(Mutex::new(writer)),
+            decoder: BufDecoder::new(),
+            closed: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub(crate) fn callback(&self) -> StreamCallback<Ret, W> {
+        StreamCallback::new(Arc::clone(&self.writer))
+    }
+}
+
+impl<Arg, Ret, R, W> std::fmt::Debug for StreamSource<Arg, Ret, R, W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(&format!(
+            "StreamSource<{}, {}>",
+            type_name::<Arg>(),
+            type_name::<Ret>()
+        ))
+        .field("closed", &self.closed)
+        .finish_non_exhaustive()
+    }
+}
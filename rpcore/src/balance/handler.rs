@@ -0,0 +1,404 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+
+use super::{RoundRobin, Strategy};
+use crate::policy::{Block, Shed};
+use crate::{callback_fn, Callback, Handler};
+
+/// The load of one backend of a [`Balance`].
+#[derive(Default)]
+pub struct Backend {
+    inflight_count: AtomicUsize,
+    queued_count: AtomicUsize,
+}
+
+impl Backend {
+    /// Returns the number of calls given to this backend that have not been
+    /// called back yet, including the queued ones.
+    pub fn inflight_count(&self) -> usize {
+        self.inflight_count.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of calls waiting for this backend.
+    pub fn queued_count(&self) -> usize {
+        self.queued_count.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Backend")
+            .field("inflight_count", &self.inflight_count())
+            .field("queued_count", &self.queued_count())
+            .finish()
+    }
+}
+
+/// A handle to read the load of the backends of a [`Balance`] from other
+/// threads.
+#[derive(Clone)]
+pub struct Load {
+    backends: Arc<[Backend]>,
+}
+
+impl Load {
+    pub fn backends(&self) -> &[Backend] {
+        &self.backends
+    }
+}
+
+impl fmt::Debug for Load {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.backends.iter()).finish()
+    }
+}
+
+type Job<H> = Box<dyn FnOnce(&mut H, Release) + Send>;
+
+/// Distributes calls over several inner handlers, the backends, each handling
+/// its calls on its own thread.
+///
+/// The backend of every call is picked by the [`Strategy`] `S`, and the call
+/// is queued until that backend is free. Callbacks are called from the
+/// backend threads.
+///
+/// Every backend queues up to `capacity` calls, an unbounded number unless
+/// set with [`Balance::with_policy`]. What happens to a call for a full queue
+/// depends on the policy `P`, which is one of [`Block`] and [`Shed`].
+///
+/// Once the balance is dropped, the backends exit after handling the queued
+/// calls.
+pub struct Balance<H, S = RoundRobin, P = Block> {
+    shared: Arc<Shared<H>>,
+    backends: Arc<[Backend]>,
+    strategy: S,
+    policy: P,
+}
+
+struct Shared<H> {
+    capacity: usize,
+    state: Mutex<State<H>>,
+    /// Notified when a job is pushed to the queue of the same index, or the
+    /// balance is dropped.
+    cvars: Vec<Condvar>,
+    /// Notified when a job is popped from any queue.
+    popped: Condvar,
+}
+
+struct State<H> {
+    queues: Vec<VecDeque<Job<H>>>,
+    /// Whether idle backends take jobs from other queues. Set on every call,
+    /// since the strategy is only known to be a [`Strategy`] of the argument
+    /// then.
+    steal: bool,
+    dropped: bool,
+}
+
+impl<H> Balance<H>
+where
+    H: Send + 'static,
+{
+    pub fn new(backends: impl IntoIterator<Item = H>) -> Self {
+        Self::with_strategy(backends, RoundRobin::default())
+    }
+}
+
+impl<H, S> Balance<H, S>
+where
+    H: Send + 'static,
+{
+    pub fn with_strategy(backends: impl IntoIterator<Item = H>, strategy: S) -> Self {
+        Self::with_policy(backends, strategy, usize::MAX, Block)
+    }
+}
+
+impl<H, S, P> Balance<H, S, P>
+where
+    H: Send + 'static,
+{
+    /// Creates a new `Balance` whose backends queue up to `capacity` calls
+    /// each, and handle the calls for a full queue by `policy`.
+    pub fn with_policy(
+        backends: impl IntoIterator<Item = H>,
+        strategy: S,
+        capacity: usize,
+        policy: P,
+    ) -> Self {
+        let inners: Vec<H> = backends.into_iter().collect();
+        assert!(!inners.is_empty(), "Backends must not be empty.");
+        assert!(capacity > 0, "Capacity must be greater than 0.");
+
+        let shared = Arc::new(Shared {
+            capacity,
+            state: Mutex::new(State {
+                queues: inners.iter().map(|_| VecDeque::new()).collect(),
+                steal: false,
+                dropped: false,
+            }),
+            cvars: inners.iter().map(|_| Condvar::new()).collect(),
+            popped: Condvar::new(),
+        });
+        let backends: Arc<[Backend]> = inners.iter().map(|_| Backend::default()).collect();
+
+        for (index, inner) in inners.into_iter().enumerate() {
+            let cloned = Arc::clone(&shared);
+            let backends = Arc::clone(&backends);
+            thread::Builder::new()
+                .name(format!("rpcore-backend-{index}"))
+                .spawn(move || work(&cloned, &backends, index, inner))
+                .expect("Failed to spawn backend thread.");
+        }
+
+        Self {
+            shared,
+            backends,
+            strategy,
+            policy,
+        }
+    }
+}
+
+impl<H, S, P> Balance<H, S, P> {
+    /// Returns a handle to read the load from other threads, e.g. to export it
+    /// as a metric.
+    pub fn load(&self) -> Load {
+        Load {
+            backends: Arc::clone(&self.backends),
+        }
+    }
+}
+
+impl<H> Shared<H> {
+    fn state(&self) -> MutexGuard<'_, State<H>> {
+        self.state.lock().unwrap()
+    }
+}
+
+/// Releases the in-flight slot of a call on the backend that handles it when
+/// dropped, so that the slot is also released if the call panics or its
+/// callback is dropped.
+struct Release {
+    backends: Arc<[Backend]>,
+    index: usize,
+}
+
+impl Release {
+    fn releasing<Ret: 'static>(
+        self,
+        callback: impl Callback<Ret = Ret>,
+    ) -> impl Callback<Ret = Ret> {
+        callback_fn(move |ret| {
+            drop(self);
+            callback.call(ret);
+        })
+    }
+}
+
+impl Drop for Release {
+    fn drop(&mut self) {
+        self.backends[self.index]
+            .inflight_count
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn work<H>(shared: &Shared<H>, backends: &Arc<[Backend]>, index: usize, mut inner: H) {
+    loop {
+        let job = {
+            let mut state = shared.state();
+            loop {
+                if let Some(job) = state.queues[index].pop_front() {
+                    backends[index].queued_count.fetch_sub(1, Ordering::Relaxed);
+                    break job;
+                }
+                if state.steal {
+                    if let Some(job) = steal(&mut state, backends, index) {
+                        break job;
+                    }
+                }
+                if state.dropped {
+                    return;
+                }
+                state = shared.cvars[index].wait(state).unwrap();
+            }
+        };
+        shared.popped.notify_one();
+
+        // A panicking call drops its callback, but must not bring down the
+        // backend.
+        let release = Release {
+            backends: Arc::clone(backends),
+            index,
+        };
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| job(&mut inner, release)));
+    }
+}
+
+/// Takes the oldest job of the longest queue, moving its in-flight slot to the
+/// backend at `thief`.
+fn steal<H>(state: &mut State<H>, backends: &[Backend], thief: usize) -> Option<Job<H>> {
+    let (victim, queue) = state
+        .queues
+        .iter_mut()
+        .enumerate()
+        .max_by_key(|(_, queue)| queue.len())?;
+    let job = queue.pop_front()?;
+
+    backends[victim]
+        .queued_count
+        .fetch_sub(1, Ordering::Relaxed);
+    backends[victim]
+        .inflight_count
+        .fetch_sub(1, Ordering::Relaxed);
+    backends[thief]
+        .inflight_count
+        .fetch_add(1, Ordering::Relaxed);
+    Some(job)
+}
+
+impl<H, S, P> Drop for Balance<H, S, P> {
+    fn drop(&mut self) {
+        self.shared.state().dropped = true;
+        for cvar in &self.shared.cvars {
+            cvar.notify_all();
+        }
+    }
+}
+
+impl<H, S: fmt::Debug, P: fmt::Debug> fmt::Debug for Balance<H, S, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Balance")
+            .field("strategy", &self.strategy)
+            .field("capacity", &self.shared.capacity)
+            .field("policy", &self.policy)
+            .field("load", &self.load())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H, S, P> Balance<H, S, P> {
+    /// Queues the call on the backend at `index`, whose queue has room.
+    fn push<Arg>(
+        &self,
+        mut state: MutexGuard<'_, State<H>>,
+        index: usize,
+        steal: bool,
+        arg: Arg,
+        callback: impl Callback<Ret = H::Ret>,
+    ) where
+        H: Handler<Arg>,
+        H::Ret: 'static,
+        Arg: Send + 'static,
+    {
+        let backend = &self.backends[index];
+        state.steal = steal;
+        backend.inflight_count.fetch_add(1, Ordering::Relaxed);
+        backend.queued_count.fetch_add(1, Ordering::Relaxed);
+        state.queues[index].push_back(Box::new(move |inner: &mut H, release: Release| {
+            inner.handle(arg, release.releasing(callback));
+        }));
+        drop(state);
+
+        if steal {
+            // Any idle backend may take the job.
+            for cvar in &self.shared.cvars {
+                cvar.notify_one();
+            }
+        } else {
+            self.shared.cvars[index].notify_one();
+        }
+    }
+}
+
+impl<H, S, Arg> Handler<Arg> for Balance<H, S, Block>
+where
+    H: Handler<Arg>,
+    H::Ret: 'static,
+    S: Strategy<Arg>,
+    Arg: Send + 'static,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        let index = self.strategy.pick(&arg, &self.backends);
+        let steal = self.strategy.steal();
+
+        let mut state = self.shared.state();
+        while state.queues[index].len() >= self.shared.capacity {
+            state = self.shared.popped.wait(state).unwrap();
+        }
+        self.push(state, index, steal, arg, callback);
+    }
+}
+
+impl<H, S, F, Arg> Handler<Arg> for Balance<H, S, Shed<F>>
+where
+    H: Handler<Arg>,
+    H::Ret: 'static,
+    S: Strategy<Arg>,
+    F: Fn() -> H::Ret,
+    Arg: Send + 'static,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        let index = self.strategy.pick(&arg, &self.backends);
+        let steal = self.strategy.steal();
+
+        let state = self.shared.state();
+        if state.queues[index].len() >= self.shared.capacity {
+            drop(state);
+            callback.call((self.policy.0)());
+            return;
+        }
+        self.push(state, index, steal, arg, callback);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    enum Action {
+        Drop,
+        Panic,
+        Reply,
+    }
+
+    struct Scripted;
+
+    impl Handler<Action> for Scripted {
+        type Ret = ();
+
+        fn handle(&mut self, action: Action, callback: impl Callback<Ret = ()>) {
+            match action {
+                Action::Drop => drop(callback),
+                Action::Panic => panic!("Backend failed."),
+                Action::Reply => callback.call(()),
+            }
+        }
+    }
+
+    #[test]
+    fn dropped_callback_releases_its_slot() {
+        let mut balance = Balance::new([Scripted]);
+        let (tx, rx) = mpsc::channel();
+
+        balance.handle(Action::Drop, callback_fn(|_| unreachable!()));
+        balance.handle(Action::Panic, callback_fn(|_| unreachable!()));
+        balance.handle(Action::Reply, callback_fn(move |()| tx.send(()).unwrap()));
+
+        // The backend handles its calls in order, so the first two have
+        // completed once the last one calls back.
+        rx.recv().unwrap();
+        let load = balance.load();
+        assert_eq!(load.backends()[0].inflight_count(), 0);
+        assert_eq!(load.backends()[0].queued_count(), 0);
+    }
+}
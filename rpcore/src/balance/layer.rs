@@ -0,0 +1,70 @@
+use super::{Balance, Block, RoundRobin, Shed};
+use crate::layer::Layer;
+
+#[derive(Debug, Clone)]
+pub struct BalanceLayer<S = RoundRobin, P = Block> {
+    backends: usize,
+    strategy: S,
+    capacity: usize,
+    policy: P,
+}
+
+impl BalanceLayer {
+    /// Balances calls over `backends` clones of the inner handler, in turn.
+    pub fn new(backends: usize) -> Self {
+        Self {
+            backends,
+            strategy: RoundRobin::default(),
+            capacity: usize::MAX,
+            policy: Block,
+        }
+    }
+}
+
+impl<S, P> BalanceLayer<S, P> {
+    pub fn strategy<T>(self, strategy: T) -> BalanceLayer<T, P> {
+        BalanceLayer {
+            backends: self.backends,
+            strategy,
+            capacity: self.capacity,
+            policy: self.policy,
+        }
+    }
+
+    /// Sets the maximum number of calls waiting for each backend, unbounded by
+    /// default. Calls for a full queue block.
+    pub const fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sheds calls for a full queue, calling back with `overloaded()` instead
+    /// of blocking.
+    pub fn shed<F>(self, overloaded: F) -> BalanceLayer<S, Shed<F>> {
+        BalanceLayer {
+            backends: self.backends,
+            strategy: self.strategy,
+            capacity: self.capacity,
+            policy: Shed(overloaded),
+        }
+    }
+}
+
+impl<H, S, P> Layer<H> for BalanceLayer<S, P>
+where
+    H: Clone + Send + 'static,
+    S: Clone,
+    P: Clone,
+{
+    type Handler = Balance<H, S, P>;
+
+    fn layer(&self, inner: H) -> Self::Handler {
+        let backends = vec![inner; self.backends];
+        Balance::with_policy(
+            backends,
+            self.strategy.clone(),
+            self.capacity,
+            self.policy.clone(),
+        )
+    }
+}
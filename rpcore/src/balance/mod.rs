@@ -0,0 +1,10 @@
+pub use crate::policy::{Block, Shed};
+
+mod handler;
+pub use handler::{Backend, Balance, Load};
+
+mod layer;
+pub use layer::BalanceLayer;
+
+mod strategy;
+pub use strategy::{LeastInflight, RoundRobin, Strategy, TokenHash, WorkStealing};
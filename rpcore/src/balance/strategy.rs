@@ -0,0 +1,68 @@
+use crate::server::GetToken;
+
+use super::Backend;
+
+/// Picks the backend of every call of a [`Balance`](super::Balance).
+pub trait Strategy<Arg> {
+    /// Returns the index of the backend in `backends` that handles `arg`.
+    fn pick(&mut self, arg: &Arg, backends: &[Backend]) -> usize;
+
+    /// Whether idle backends take queued calls from busy ones.
+    fn steal(&self) -> bool {
+        false
+    }
+}
+
+/// Picks the backends in turn.
+#[derive(Debug, Clone, Default)]
+pub struct RoundRobin {
+    next: usize,
+}
+
+impl<Arg> Strategy<Arg> for RoundRobin {
+    fn pick(&mut self, _arg: &Arg, backends: &[Backend]) -> usize {
+        let index = self.next % backends.len();
+        self.next = index + 1;
+        index
+    }
+}
+
+/// Picks the backend with the fewest in-flight calls, the first one on ties.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LeastInflight;
+
+impl<Arg> Strategy<Arg> for LeastInflight {
+    fn pick(&mut self, _arg: &Arg, backends: &[Backend]) -> usize {
+        backends
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, backend)| backend.inflight_count())
+            .map_or(0, |(index, _)| index)
+    }
+}
+
+/// Picks the backend by the token of the argument, so that all calls from a
+/// client are handled by the same backend, in order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokenHash;
+
+impl<Arg: GetToken> Strategy<Arg> for TokenHash {
+    fn pick(&mut self, arg: &Arg, backends: &[Backend]) -> usize {
+        usize::from(arg.token()) % backends.len()
+    }
+}
+
+/// Picks backends with `S`, and lets idle backends take queued calls from busy
+/// ones.
+#[derive(Debug, Clone, Default)]
+pub struct WorkStealing<S = RoundRobin>(pub S);
+
+impl<Arg, S: Strategy<Arg>> Strategy<Arg> for WorkStealing<S> {
+    fn pick(&mut self, arg: &Arg, backends: &[Backend]) -> usize {
+        self.0.pick(arg, backends)
+    }
+
+    fn steal(&self) -> bool {
+        true
+    }
+}
@@ -1,27 +1,303 @@
-use std::sync::{Arc, Condvar, Mutex};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
 
+use crate::policy::{Block, Shed};
 use crate::{callback_fn, Callback, Handler};
 
-#[derive(Debug)]
-pub struct ConcurrencyLimit<H> {
-    inner: H,
+/// Queues up to `backlog` calls over the limit, and sheds the calls beyond
+/// that, calling back with the value returned by `overloaded`.
+///
+/// # Threads
+///
+/// **Queued calls are not handled on the serve thread.** A queued call is
+/// handled by whichever thread frees a slot, i.e. calls back an in-flight
+/// call: a worker of the inner handler, but also a client or timer thread if
+/// the callback is called there. It runs right after that callback returns.
+///
+/// The inner handler is therefore shared behind a lock, and it and the
+/// arguments must be `Send`:
+///
+/// ```
+/// # use std::sync::mpsc;
+/// # use std::thread;
+/// # use rpcore::concurrency_limit::{ConcurrencyLimit, Queue};
+/// # use rpcore::{callback_fn, Callback, Handler};
+/// /// Replies with the thread it handled the call on, once told to.
+/// struct Deferred(mpsc::Sender<Box<dyn FnOnce() + Send>>);
+///
+/// impl Handler<()> for Deferred {
+///     type Ret = thread::ThreadId;
+///
+///     fn handle(&mut self, _: (), callback: impl Callback<Ret = Self::Ret>) {
+///         let id = thread::current().id();
+///         self.0.send(Box::new(move || callback.call(id))).unwrap();
+///     }
+/// }
+///
+/// let (tx, rx) = mpsc::channel();
+/// let queue = Queue { backlog: 1, overloaded: || unreachable!() };
+/// let mut limit = ConcurrencyLimit::with_policy(Deferred(tx), 1, queue);
+///
+/// let (ids_tx, ids) = mpsc::channel();
+/// for _ in 0..2 {
+///     let ids_tx = ids_tx.clone();
+///     limit.handle((), callback_fn(move |id| ids_tx.send(id).unwrap()));
+/// }
+/// assert_eq!(limit.load().queued_count(), 1);
+///
+/// // Calling back the first call on another thread handles the queued call
+/// // there.
+/// let reply = rx.recv().unwrap();
+/// thread::spawn(reply).join().unwrap();
+/// rx.recv().unwrap()();
+///
+/// let serve_thread = thread::current().id();
+/// assert_eq!(ids.recv().unwrap(), serve_thread);
+/// assert_ne!(ids.recv().unwrap(), serve_thread);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Queue<F> {
+    pub backlog: usize,
+    pub overloaded: F,
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A policy of a [`ConcurrencyLimit`], one of [`Block`], [`Shed`] and
+/// [`Queue`].
+///
+/// It decides how the inner handler is held: [`Block`] and [`Shed`] own it,
+/// [`Queue`] shares it with the threads that handle queued calls.
+pub trait Policy: sealed::Sealed {
+    #[doc(hidden)]
+    type Inner<H>;
+
+    #[doc(hidden)]
+    fn hold<H>(inner: H) -> Self::Inner<H>;
+}
+
+impl sealed::Sealed for Block {}
+
+impl Policy for Block {
+    type Inner<H> = H;
+
+    fn hold<H>(inner: H) -> H {
+        inner
+    }
+}
+
+impl<F> sealed::Sealed for Shed<F> {}
+
+impl<F> Policy for Shed<F> {
+    type Inner<H> = H;
+
+    fn hold<H>(inner: H) -> H {
+        inner
+    }
+}
+
+impl<F> sealed::Sealed for Queue<F> {}
+
+impl<F> Policy for Queue<F> {
+    type Inner<H> = Arc<Mutex<H>>;
+
+    fn hold<H>(inner: H) -> Arc<Mutex<H>> {
+        Arc::new(Mutex::new(inner))
+    }
+}
+
+/// Limits the number of in-flight calls, i.e. calls whose callback has not
+/// been called yet.
+///
+/// What happens to calls over the limit depends on the [`Policy`] `P`. Only
+/// [`Block`] blocks the serve thread, and only [`Queue`] handles calls on
+/// other threads.
+pub struct ConcurrencyLimit<H, P: Policy = Block> {
+    inner: P::Inner<H>,
+    shared: Arc<Shared>,
+    policy: P,
+}
+
+struct Shared {
     limit: u32,
-    inflight_count: Arc<(Mutex<u32>, Condvar)>,
+    state: Mutex<State>,
+    cvar: Condvar,
+}
+
+struct State {
+    inflight_count: u32,
+    queue: VecDeque<Box<dyn FnOnce() + Send>>,
+    /// Set while a thread is handling queued calls.
+    dispatching: bool,
 }
 
 impl<H> ConcurrencyLimit<H> {
     pub fn new(inner: H, limit: u32) -> Self {
+        Self::with_policy(inner, limit, Block)
+    }
+}
+
+impl<H, P: Policy> ConcurrencyLimit<H, P> {
+    pub fn with_policy(inner: H, limit: u32, policy: P) -> Self {
         assert!(limit > 0, "Limit must be greater than 0.");
 
         Self {
-            inner,
-            limit,
-            inflight_count: Default::default(),
+            inner: P::hold(inner),
+            shared: Arc::new(Shared {
+                limit,
+                state: Mutex::new(State {
+                    inflight_count: 0,
+                    queue: VecDeque::new(),
+                    dispatching: false,
+                }),
+                cvar: Condvar::new(),
+            }),
+            policy,
+        }
+    }
+
+    /// Returns a handle to read the load from other threads, e.g. to export it
+    /// as a metric.
+    pub fn load(&self) -> Load {
+        Load {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    pub fn inflight_count(&self) -> u32 {
+        self.load().inflight_count()
+    }
+}
+
+/// A handle to read the load of a [`ConcurrencyLimit`].
+#[derive(Clone)]
+pub struct Load {
+    shared: Arc<Shared>,
+}
+
+impl Load {
+    pub fn inflight_count(&self) -> u32 {
+        self.shared.state().inflight_count
+    }
+
+    /// Returns the number of calls waiting in the [`Queue`].
+    pub fn queued_count(&self) -> usize {
+        self.shared.state().queue.len()
+    }
+}
+
+impl fmt::Debug for Load {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.shared.state();
+        f.debug_struct("Load")
+            .field("inflight_count", &state.inflight_count)
+            .field("queued_count", &state.queue.len())
+            .finish()
+    }
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    /// Wraps `callback` so that it releases its in-flight slot when called,
+    /// or when dropped without being called.
+    fn releasing<Ret: 'static>(
+        self: &Arc<Self>,
+        callback: impl Callback<Ret = Ret>,
+    ) -> impl Callback<Ret = Ret> {
+        let mut release = Release {
+            shared: Some(Arc::clone(self)),
+        };
+        callback_fn(move |ret| {
+            let shared = release.shared.take().unwrap();
+            shared.release();
+
+            callback.call(ret);
+            shared.dispatch();
+        })
+    }
+
+    fn release(&self) {
+        self.state().inflight_count -= 1;
+        self.cvar.notify_one();
+    }
+
+    /// Handles queued calls while there are free slots, unless another thread
+    /// is already doing so.
+    ///
+    /// A queued call that completes synchronously re-enters here and returns
+    /// immediately, the next queued call is then picked up by the loop.
+    fn dispatch(&self) {
+        let mut state = self.state();
+        if state.dispatching {
+            return;
+        }
+        state.dispatching = true;
+        let _guard = DispatchGuard { shared: self };
+
+        loop {
+            if state.inflight_count >= self.limit {
+                break;
+            }
+            let Some(task) = state.queue.pop_front() else {
+                break;
+            };
+            state.inflight_count += 1;
+            drop(state);
+
+            task();
+            state = self.state();
         }
+        state.dispatching = false;
     }
 }
 
-impl<H, Arg> Handler<Arg> for ConcurrencyLimit<H>
+/// Releases an in-flight slot when dropped, unless the callback holding it
+/// took it to release it before calling back.
+struct Release {
+    shared: Option<Arc<Shared>>,
+}
+
+impl Drop for Release {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.take() {
+            shared.release();
+            shared.dispatch();
+        }
+    }
+}
+
+/// Clears `dispatching` if a queued call panics, so that the queue is still
+/// dispatched afterwards.
+struct DispatchGuard<'a> {
+    shared: &'a Shared,
+}
+
+impl Drop for DispatchGuard<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.shared.state().dispatching = false;
+        }
+    }
+}
+
+impl<H, P: Policy + fmt::Debug> fmt::Debug for ConcurrencyLimit<H, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrencyLimit")
+            .field("limit", &self.shared.limit)
+            .field("policy", &self.policy)
+            .field("load", &self.load())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H, Arg> Handler<Arg> for ConcurrencyLimit<H, Block>
 where
     H: Handler<Arg>,
     H::Ret: 'static,
@@ -30,26 +306,133 @@ where
 
     fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
         {
-            let (count, cvar) = self.inflight_count.as_ref();
-            let mut count = count.lock().unwrap();
-            while *count >= self.limit {
-                count = cvar.wait(count).unwrap();
+            let mut state = self.shared.state();
+            while state.inflight_count >= self.shared.limit {
+                state = self.shared.cvar.wait(state).unwrap();
             }
-            *count += 1;
+            state.inflight_count += 1;
         }
 
-        let cloned = Arc::clone(&self.inflight_count);
+        let callback = self.shared.releasing(callback);
+        self.inner.handle(arg, callback);
+    }
+}
 
-        self.inner.handle(
-            arg,
-            callback_fn(move |ret| {
-                let (count, cvar) = cloned.as_ref();
-                let mut count = count.lock().unwrap();
-                *count -= 1;
-                cvar.notify_one();
+impl<H, F, Arg> Handler<Arg> for ConcurrencyLimit<H, Shed<F>>
+where
+    H: Handler<Arg>,
+    H::Ret: 'static,
+    F: Fn() -> H::Ret,
+{
+    type Ret = H::Ret;
 
-                callback.call(ret);
-            }),
-        );
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        {
+            let mut state = self.shared.state();
+            if state.inflight_count >= self.shared.limit {
+                drop(state);
+                callback.call((self.policy.0)());
+                return;
+            }
+            state.inflight_count += 1;
+        }
+
+        let callback = self.shared.releasing(callback);
+        self.inner.handle(arg, callback);
+    }
+}
+
+impl<H, F, Arg> Handler<Arg> for ConcurrencyLimit<H, Queue<F>>
+where
+    H: Handler<Arg> + Send + 'static,
+    H::Ret: 'static,
+    Arg: Send + 'static,
+    F: Fn() -> H::Ret,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        {
+            let mut state = self.shared.state();
+            if state.inflight_count >= self.shared.limit && state.queue.len() >= self.policy.backlog
+            {
+                drop(state);
+                callback.call((self.policy.overloaded)());
+                return;
+            }
+
+            let inner = Arc::clone(&self.inner);
+            let shared = Arc::clone(&self.shared);
+            state.queue.push_back(Box::new(move || {
+                let callback = shared.releasing(callback);
+                inner.lock().unwrap().handle(arg, callback);
+            }));
+        }
+
+        self.shared.dispatch();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    /// Hands its callbacks over, to be called or dropped later.
+    struct Holder(mpsc::Sender<Box<dyn FnOnce(bool) + Send>>);
+
+    impl Handler<()> for Holder {
+        type Ret = ();
+
+        fn handle(&mut self, _: (), callback: impl Callback<Ret = ()>) {
+            self.0
+                .send(Box::new(move |call| {
+                    if call {
+                        callback.call(());
+                    }
+                }))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn dropped_callback_releases_its_slot() {
+        let (tx, rx) = mpsc::channel();
+        let mut limit = ConcurrencyLimit::with_policy(Holder(tx), 1, Shed(|| ()));
+
+        limit.handle((), callback_fn(|_| {}));
+        assert_eq!(limit.inflight_count(), 1);
+
+        // Dropped without being called back.
+        rx.recv().unwrap()(false);
+        assert_eq!(limit.inflight_count(), 0);
+
+        limit.handle((), callback_fn(|_| {}));
+        assert_eq!(limit.inflight_count(), 1);
+        rx.recv().unwrap()(true);
+        assert_eq!(limit.inflight_count(), 0);
+    }
+
+    #[test]
+    fn dropped_callback_dispatches_the_queue() {
+        let (tx, rx) = mpsc::channel();
+        let queue = Queue {
+            backlog: 1,
+            overloaded: || unreachable!(),
+        };
+        let mut limit = ConcurrencyLimit::with_policy(Holder(tx), 1, queue);
+
+        limit.handle((), callback_fn(|_| {}));
+        limit.handle((), callback_fn(|_| {}));
+        assert_eq!(limit.inflight_count(), 1);
+        assert_eq!(limit.load().queued_count(), 1);
+
+        rx.recv().unwrap()(false);
+        assert_eq!(limit.inflight_count(), 1);
+        assert_eq!(limit.load().queued_count(), 0);
+
+        rx.recv().unwrap()(false);
+        assert_eq!(limit.inflight_count(), 0);
     }
 }
@@ -1,21 +1,48 @@
-use super::ConcurrencyLimit;
+use super::{Block, ConcurrencyLimit, Policy, Queue, Shed};
 use crate::layer::Layer;
 
 #[derive(Debug, Clone)]
-pub struct ConcurrencyLimitLayer {
+pub struct ConcurrencyLimitLayer<P = Block> {
     limit: u32,
+    policy: P,
 }
 
 impl ConcurrencyLimitLayer {
     pub const fn new(limit: u32) -> Self {
-        Self { limit }
+        Self {
+            limit,
+            policy: Block,
+        }
     }
 }
 
-impl<H> Layer<H> for ConcurrencyLimitLayer {
-    type Handler = ConcurrencyLimit<H>;
+impl<P> ConcurrencyLimitLayer<P> {
+    /// Sheds calls over the limit, calling back with `overloaded()` instead of
+    /// blocking.
+    pub fn shed<F>(self, overloaded: F) -> ConcurrencyLimitLayer<Shed<F>> {
+        ConcurrencyLimitLayer {
+            limit: self.limit,
+            policy: Shed(overloaded),
+        }
+    }
+
+    /// Queues up to `backlog` calls over the limit, and sheds the calls beyond
+    /// that, calling back with `overloaded()`.
+    pub fn queue<F>(self, backlog: usize, overloaded: F) -> ConcurrencyLimitLayer<Queue<F>> {
+        ConcurrencyLimitLayer {
+            limit: self.limit,
+            policy: Queue {
+                backlog,
+                overloaded,
+            },
+        }
+    }
+}
+
+impl<H, P: Policy + Clone> Layer<H> for ConcurrencyLimitLayer<P> {
+    type Handler = ConcurrencyLimit<H, P>;
 
     fn layer(&self, inner: H) -> Self::Handler {
-        ConcurrencyLimit::new(inner, self.limit)
+        ConcurrencyLimit::with_policy(inner, self.limit, self.policy.clone())
     }
 }
@@ -1,5 +1,7 @@
+pub use crate::policy::{Block, Shed};
+
 mod handler;
-pub use handler::ConcurrencyLimit;
+pub use handler::{ConcurrencyLimit, Load, Policy, Queue};
 
 mod layer;
 pub use layer::ConcurrencyLimitLayer;
@@ -1,5 +1,18 @@
 pub use rpcore_core::*;
+#[cfg(feature = "macros")]
+pub use rpcore_macros::service;
 
+pub mod balance;
 pub mod concurrency_limit;
 #[cfg(feature = "log")]
 pub mod log;
+pub mod metrics;
+pub mod policy;
+pub mod rate_limit;
+pub mod retry;
+pub mod thread_pool;
+pub mod timeout;
+#[cfg(feature = "tracing")]
+pub mod trace;
+
+mod timer;
@@ -1,24 +1,101 @@
 use std::any::type_name;
 use std::fmt;
+use std::sync::Arc;
 use std::time::Instant;
 
+use log::Level;
+
 use crate::{callback_fn, Callback, Handler};
 
+/// Formats the args or rets of a [`Log`] handler.
+///
+/// It is implemented by [`DebugFormat`], and by closures taking a reference
+/// to the value.
+pub trait Format<T> {
+    fn format(&self, value: &T) -> String;
+}
+
+impl<T, F> Format<T> for F
+where
+    F: Fn(&T) -> String,
+{
+    fn format(&self, value: &T) -> String {
+        self(value)
+    }
+}
+
+/// Formats values with their `Debug` implementation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DebugFormat;
+
+impl<T: fmt::Debug> Format<T> for DebugFormat {
+    fn format(&self, value: &T) -> String {
+        format!("{value:?}")
+    }
+}
+
 #[derive(Debug)]
-pub struct Log<H> {
+pub struct Log<H, FA = DebugFormat, FR = DebugFormat> {
     inner: H,
     handler_name: String,
+    level: Level,
+    max_len: Option<usize>,
+    sample_rate: f64,
+    /// Accumulates `sample_rate` on every call, a call is logged whenever it
+    /// reaches 1.
+    sample_acc: f64,
+    format_arg: FA,
+    format_ret: Arc<FR>,
 }
 
 impl<H> Log<H> {
     pub fn new(inner: H, handler_name: Option<String>) -> Self {
+        Self::with_formats(inner, handler_name, DebugFormat, DebugFormat)
+    }
+}
+
+impl<H, FA, FR> Log<H, FA, FR> {
+    /// Creates a new `Log` that formats args with `format_arg` and rets with
+    /// `format_ret`.
+    pub fn with_formats(
+        inner: H,
+        handler_name: Option<String>,
+        format_arg: FA,
+        format_ret: FR,
+    ) -> Self {
         let handler_name = handler_name.unwrap_or(type_name::<H>().to_owned());
         Self {
             inner,
             handler_name,
+            level: Level::Info,
+            max_len: None,
+            sample_rate: 1.0,
+            sample_acc: 0.0,
+            format_arg,
+            format_ret: Arc::new(format_ret),
         }
     }
 
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Truncates the formatted args and rets to `max_len` bytes, `None` for
+    /// no limit.
+    pub fn max_len(mut self, max_len: Option<usize>) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Logs only a `sample_rate` fraction of the calls, evenly spread.
+    ///
+    /// The rate is clamped between 0 and 1, and NaN counts as 0.
+    pub fn sample_rate(mut self, sample_rate: f64) -> Self {
+        self.sample_rate = clamp_sample_rate(sample_rate);
+        self
+    }
+
     pub fn get_ref(&self) -> &H {
         &self.inner
     }
@@ -30,28 +107,72 @@ impl<H> Log<H> {
     pub fn into_inner(self) -> H {
         self.inner
     }
+
+    fn sample(&mut self) -> bool {
+        self.sample_acc += self.sample_rate;
+        if self.sample_acc >= 1.0 {
+            self.sample_acc -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+pub(super) fn clamp_sample_rate(sample_rate: f64) -> f64 {
+    if sample_rate.is_nan() {
+        0.0
+    } else {
+        sample_rate.clamp(0.0, 1.0)
+    }
+}
+
+fn truncate(mut s: String, max_len: Option<usize>) -> String {
+    if let Some(max_len) = max_len {
+        if s.len() > max_len {
+            let mut end = max_len;
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+            s.truncate(end);
+            s.push_str("...");
+        }
+    }
+    s
 }
 
-impl<H, Arg> Handler<Arg> for Log<H>
+impl<H, FA, FR, Arg> Handler<Arg> for Log<H, FA, FR>
 where
     H: Handler<Arg>,
-    H::Ret: fmt::Debug + 'static,
-    Arg: fmt::Debug,
+    H::Ret: 'static,
+    FA: Format<Arg>,
+    FR: Format<H::Ret> + Send + Sync + 'static,
 {
     type Ret = H::Ret;
 
     fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        // Nothing is formatted for calls that are not logged.
+        if !log::log_enabled!(self.level) || !self.sample() {
+            self.inner.handle(arg, callback);
+            return;
+        }
+
         let begin_at = Instant::now();
         let handler_name = self.handler_name.clone();
-        let formatted_arg = format!("{arg:?}");
-        log::info!("[{handler_name}] handling {formatted_arg}");
+        let level = self.level;
+        let max_len = self.max_len;
+        let format_ret = Arc::clone(&self.format_ret);
+        let formatted_arg = truncate(self.format_arg.format(&arg), max_len);
+        log::log!(level, "[{handler_name}] handling {formatted_arg}");
 
         self.inner.handle(
             arg,
             callback_fn(move |ret| {
                 let elapsed = begin_at.elapsed();
-                log::info!(
-                    "[{handler_name}] handled  {formatted_arg}, ret: {ret:?}, elapsed: {elapsed:?}"
+                let formatted_ret = truncate(format_ret.format(&ret), max_len);
+                log::log!(
+                    level,
+                    "[{handler_name}] handled  {formatted_arg}, ret: {formatted_ret}, elapsed: {elapsed:?}"
                 );
                 callback.call(ret);
             }),
@@ -1,15 +1,105 @@
-use super::Log;
+use log::Level;
+
+use super::handler::clamp_sample_rate;
+use super::{DebugFormat, Log};
 use crate::layer::Layer;
 
-#[derive(Debug, Clone, Default)]
-pub struct LogLayer {
+/// Logs every call and its return value.
+///
+/// By default, calls are logged at the `info` level with the `Debug` of their
+/// args and rets, under the type name of the handler.
+#[derive(Debug, Clone)]
+pub struct LogLayer<FA = DebugFormat, FR = DebugFormat> {
     handler_name: Option<String>,
+    level: Level,
+    max_len: Option<usize>,
+    sample_rate: f64,
+    format_arg: FA,
+    format_ret: FR,
+}
+
+impl Default for LogLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogLayer {
+    pub const fn new() -> Self {
+        Self {
+            handler_name: None,
+            level: Level::Info,
+            max_len: None,
+            sample_rate: 1.0,
+            format_arg: DebugFormat,
+            format_ret: DebugFormat,
+        }
+    }
+}
+
+impl<FA, FR> LogLayer<FA, FR> {
+    pub fn handler_name(mut self, handler_name: impl Into<String>) -> Self {
+        self.handler_name = Some(handler_name.into());
+        self
+    }
+
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Truncates the formatted args and rets to `max_len` bytes, `None` for
+    /// no limit.
+    pub fn max_len(mut self, max_len: Option<usize>) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Logs only a `sample_rate` fraction of the calls.
+    ///
+    /// The rate is clamped between 0 and 1, and NaN counts as 0.
+    pub fn sample_rate(mut self, sample_rate: f64) -> Self {
+        self.sample_rate = clamp_sample_rate(sample_rate);
+        self
+    }
+
+    /// Formats args with `format_arg`, e.g. to redact sensitive fields.
+    pub fn format_arg<F>(self, format_arg: F) -> LogLayer<F, FR> {
+        LogLayer {
+            handler_name: self.handler_name,
+            level: self.level,
+            max_len: self.max_len,
+            sample_rate: self.sample_rate,
+            format_arg,
+            format_ret: self.format_ret,
+        }
+    }
+
+    /// Formats rets with `format_ret`, e.g. to redact sensitive fields.
+    pub fn format_ret<F>(self, format_ret: F) -> LogLayer<FA, F> {
+        LogLayer {
+            handler_name: self.handler_name,
+            level: self.level,
+            max_len: self.max_len,
+            sample_rate: self.sample_rate,
+            format_arg: self.format_arg,
+            format_ret,
+        }
+    }
 }
 
-impl<H> Layer<H> for LogLayer {
-    type Handler = Log<H>;
+impl<H, FA: Clone, FR: Clone> Layer<H> for LogLayer<FA, FR> {
+    type Handler = Log<H, FA, FR>;
 
     fn layer(&self, inner: H) -> Self::Handler {
-        Log::new(inner, self.handler_name.clone())
+        Log::with_formats(
+            inner,
+            self.handler_name.clone(),
+            self.format_arg.clone(),
+            self.format_ret.clone(),
+        )
+        .level(self.level)
+        .max_len(self.max_len)
+        .sample_rate(self.sample_rate)
     }
 }
@@ -1,5 +1,5 @@
 mod handler;
-pub use handler::Log;
+pub use handler::{DebugFormat, Format, Log};
 
 mod layer;
 pub use layer::LogLayer;
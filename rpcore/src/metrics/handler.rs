@@ -0,0 +1,90 @@
+use std::any::type_name;
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::Recorder;
+use crate::{callback_fn, Callback, Handler};
+
+#[derive(Debug)]
+pub struct Metrics<H, R> {
+    inner: H,
+    handler_name: Arc<str>,
+    recorder: Arc<R>,
+}
+
+impl<H, R> Metrics<H, R> {
+    pub fn new(inner: H, recorder: Arc<R>, handler_name: Option<String>) -> Self {
+        let handler_name = handler_name.unwrap_or(type_name::<H>().to_owned());
+        Self {
+            inner,
+            handler_name: handler_name.into(),
+            recorder,
+        }
+    }
+
+    pub fn get_ref(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut H {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+}
+
+impl<H, R, Arg> Handler<Arg> for Metrics<H, R>
+where
+    H: Handler<Arg>,
+    H::Ret: 'static,
+    R: Recorder,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        let begin_at = Instant::now();
+        self.recorder.on_request(&self.handler_name);
+
+        let guard = Guard {
+            handler_name: Arc::clone(&self.handler_name),
+            recorder: Arc::clone(&self.recorder),
+            begin_at,
+            responded: false,
+        };
+        self.inner.handle(
+            arg,
+            callback_fn(move |ret| {
+                guard.respond();
+                callback.call(ret);
+            }),
+        );
+    }
+}
+
+/// Records the end of a call when dropped, as a response if
+/// [`Guard::respond`] was called, and as a dropped call otherwise.
+struct Guard<R: Recorder> {
+    handler_name: Arc<str>,
+    recorder: Arc<R>,
+    begin_at: Instant,
+    responded: bool,
+}
+
+impl<R: Recorder> Guard<R> {
+    fn respond(mut self) {
+        self.responded = true;
+    }
+}
+
+impl<R: Recorder> Drop for Guard<R> {
+    fn drop(&mut self) {
+        let elapsed = self.begin_at.elapsed();
+        if self.responded {
+            self.recorder.on_response(&self.handler_name, elapsed);
+        } else {
+            self.recorder.on_dropped(&self.handler_name, elapsed);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use super::Metrics;
+use crate::layer::Layer;
+
+#[derive(Debug)]
+pub struct MetricsLayer<R> {
+    recorder: Arc<R>,
+    handler_name: Option<String>,
+}
+
+impl<R> MetricsLayer<R> {
+    /// Records into `recorder`, which can be shared with other layers and read
+    /// elsewhere.
+    pub fn new(recorder: Arc<R>) -> Self {
+        Self {
+            recorder,
+            handler_name: None,
+        }
+    }
+
+    /// Sets the name the metrics are recorded under, the type name of the
+    /// handler by default.
+    pub fn handler_name(mut self, handler_name: impl Into<String>) -> Self {
+        self.handler_name = Some(handler_name.into());
+        self
+    }
+}
+
+impl<R> Clone for MetricsLayer<R> {
+    fn clone(&self) -> Self {
+        Self {
+            recorder: Arc::clone(&self.recorder),
+            handler_name: self.handler_name.clone(),
+        }
+    }
+}
+
+impl<H, R> Layer<H> for MetricsLayer<R> {
+    type Handler = Metrics<H, R>;
+
+    fn layer(&self, inner: H) -> Self::Handler {
+        Metrics::new(inner, Arc::clone(&self.recorder), self.handler_name.clone())
+    }
+}
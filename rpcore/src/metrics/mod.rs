@@ -0,0 +1,8 @@
+mod handler;
+pub use handler::Metrics;
+
+mod layer;
+pub use layer::MetricsLayer;
+
+mod recorder;
+pub use recorder::{HandlerMetrics, Histogram, InMemoryRecorder, Recorder};
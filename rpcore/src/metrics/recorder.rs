@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Receives the measurements of [`Metrics`](super::Metrics) handlers.
+///
+/// Implement it to export the measurements to a metrics system.
+pub trait Recorder: Send + Sync + 'static {
+    /// Called when `handler` starts handling a call.
+    fn on_request(&self, handler: &str);
+
+    /// Called when `handler` calls back, `elapsed` after the call started.
+    fn on_response(&self, handler: &str, elapsed: Duration);
+
+    /// Called instead of [`on_response`](Recorder::on_response) when the
+    /// callback is dropped without being called, e.g. by a handler that
+    /// failed, `elapsed` after the call started.
+    fn on_dropped(&self, handler: &str, elapsed: Duration);
+}
+
+/// A [`Recorder`] that keeps the metrics in memory, to be read with
+/// [`InMemoryRecorder::snapshot`].
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use rpcore::metrics::{InMemoryRecorder, MetricsLayer};
+/// # use rpcore::{callback_fn, Callback, Handler, HandlerBuilder};
+/// struct Echo;
+///
+/// impl Handler<u32> for Echo {
+///     type Ret = u32;
+///
+///     fn handle(&mut self, arg: u32, callback: impl Callback<Ret = u32>) {
+///         callback.call(arg);
+///     }
+/// }
+///
+/// let recorder = Arc::new(InMemoryRecorder::new());
+/// let mut handler = HandlerBuilder::new()
+///     .layer(MetricsLayer::new(Arc::clone(&recorder)).handler_name("echo"))
+///     .handler(Echo);
+/// handler.handle(1, callback_fn(|_| {}));
+///
+/// let metrics = &recorder.snapshot()["echo"];
+/// assert_eq!(metrics.requests, 1);
+/// assert_eq!(metrics.inflight, 0);
+/// assert_eq!(metrics.dropped, 0);
+/// assert_eq!(metrics.latency.count(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct InMemoryRecorder {
+    handlers: Mutex<HashMap<String, HandlerMetrics>>,
+}
+
+impl InMemoryRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the metrics of every handler that has been called.
+    pub fn snapshot(&self) -> HashMap<String, HandlerMetrics> {
+        self.handlers.lock().unwrap().clone()
+    }
+
+    fn update(&self, handler: &str, f: impl FnOnce(&mut HandlerMetrics)) {
+        let mut handlers = self.handlers.lock().unwrap();
+        match handlers.get_mut(handler) {
+            Some(metrics) => f(metrics),
+            None => f(handlers.entry(handler.to_owned()).or_default()),
+        }
+    }
+}
+
+impl Recorder for InMemoryRecorder {
+    fn on_request(&self, handler: &str) {
+        self.update(handler, |metrics| {
+            metrics.requests += 1;
+            metrics.inflight += 1;
+        });
+    }
+
+    fn on_response(&self, handler: &str, elapsed: Duration) {
+        self.update(handler, |metrics| {
+            metrics.inflight = metrics.inflight.saturating_sub(1);
+            metrics.latency.record(elapsed);
+        });
+    }
+
+    fn on_dropped(&self, handler: &str, _elapsed: Duration) {
+        self.update(handler, |metrics| {
+            metrics.inflight = metrics.inflight.saturating_sub(1);
+            metrics.dropped += 1;
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HandlerMetrics {
+    /// The number of calls started.
+    pub requests: u64,
+    /// The number of calls started but not called back yet.
+    pub inflight: u64,
+    /// The number of calls whose callback was dropped without being called.
+    pub dropped: u64,
+    /// The latency of the calls that were called back.
+    pub latency: Histogram,
+}
+
+/// The upper bounds of the latency buckets, from 1µs to 10s.
+const BUCKET_BOUNDS: [Duration; 8] = [
+    Duration::from_micros(1),
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+    Duration::from_secs(10),
+];
+
+/// A latency histogram with power-of-ten buckets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Histogram {
+    /// `counts[i]` is the number of samples not above `BUCKET_BOUNDS[i]`, and
+    /// the last count is that of samples above all bounds.
+    counts: [u64; BUCKET_BOUNDS.len() + 1],
+    count: u64,
+    sum: Duration,
+}
+
+impl Histogram {
+    pub fn record(&mut self, sample: Duration) {
+        let i = BUCKET_BOUNDS.partition_point(|&bound| bound < sample);
+        self.counts[i] += 1;
+        self.count += 1;
+        self.sum += sample;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    pub fn mean(&self) -> Option<Duration> {
+        let count = u32::try_from(self.count).ok().filter(|&n| n > 0)?;
+        Some(self.sum / count)
+    }
+
+    /// Returns the buckets as pairs of an upper bound and the number of
+    /// samples in the bucket. The bound of the last bucket is `None`.
+    pub fn buckets(&self) -> impl Iterator<Item = (Option<Duration>, u64)> + '_ {
+        BUCKET_BOUNDS
+            .iter()
+            .map(|&bound| Some(bound))
+            .chain([None])
+            .zip(self.counts.iter().copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_samples_by_upper_bound() {
+        let mut histogram = Histogram::default();
+        assert_eq!(histogram.mean(), None);
+
+        for sample in [
+            Duration::ZERO,
+            Duration::from_micros(1),
+            Duration::from_micros(2),
+            Duration::from_millis(5),
+            Duration::from_secs(60),
+        ] {
+            histogram.record(sample);
+        }
+
+        let counts: Vec<_> = histogram.buckets().map(|(_, count)| count).collect();
+        assert_eq!(counts, [2, 1, 0, 0, 1, 0, 0, 0, 1]);
+        assert_eq!(histogram.buckets().last(), Some((None, 1)));
+        assert_eq!(histogram.count(), 5);
+        assert_eq!(
+            histogram.sum(),
+            Duration::from_secs(60) + Duration::from_micros(5003)
+        );
+        assert_eq!(histogram.mean(), Some(histogram.sum() / 5));
+    }
+
+    #[test]
+    fn snapshot_tracks_each_handler() {
+        let recorder = InMemoryRecorder::new();
+        recorder.on_request("a");
+        recorder.on_request("a");
+        recorder.on_request("a");
+        recorder.on_request("b");
+        recorder.on_response("a", Duration::from_millis(2));
+        recorder.on_dropped("a", Duration::from_millis(3));
+
+        let snapshot = recorder.snapshot();
+        let a = &snapshot["a"];
+        assert_eq!((a.requests, a.inflight, a.dropped), (3, 1, 1));
+        // Dropped calls are not sampled.
+        assert_eq!(a.latency.count(), 1);
+        assert_eq!(a.latency.sum(), Duration::from_millis(2));
+
+        let b = &snapshot["b"];
+        assert_eq!((b.requests, b.inflight, b.dropped), (1, 1, 0));
+        assert_eq!(b.latency, Histogram::default());
+
+        // A snapshot is a copy, unaffected by later calls.
+        recorder.on_response("b", Duration::from_millis(1));
+        assert_eq!(snapshot["b"].inflight, 1);
+        assert_eq!(recorder.snapshot()["b"].inflight, 0);
+    }
+}
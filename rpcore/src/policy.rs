@@ -0,0 +1,15 @@
+//! The policies of the layers that limit calls, such as
+//! [`ConcurrencyLimit`](crate::concurrency_limit::ConcurrencyLimit),
+//! [`RateLimit`](crate::rate_limit::RateLimit),
+//! [`ThreadPool`](crate::thread_pool::ThreadPool) and
+//! [`Balance`](crate::balance::Balance), which decide what happens to a call
+//! over the limit.
+
+/// Blocks `handle` until the call is within the limit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Block;
+
+/// Sheds calls over the limit, calling back with the value returned by the
+/// closure.
+#[derive(Debug, Clone, Copy)]
+pub struct Shed<F>(pub F);
@@ -0,0 +1,137 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::policy::{Block, Shed};
+use crate::{Callback, Handler};
+
+/// Limits the rate of `handle` calls with a token bucket.
+///
+/// The bucket holds up to `burst` tokens and gains one every `per / num`.
+/// Every call takes a token, and what happens when there is none depends on
+/// the policy `P`, which is either [`Block`], blocking until a token is
+/// available, or [`Shed`], shedding calls while there is none.
+#[derive(Debug)]
+pub struct RateLimit<H, P = Block> {
+    inner: H,
+    bucket: Bucket,
+    policy: P,
+}
+
+impl<H> RateLimit<H> {
+    /// Creates a new `RateLimit` that allows `num` calls per `per`, in bursts
+    /// of up to `num` calls, and blocks when the limit is reached.
+    pub fn new(inner: H, num: u32, per: Duration) -> Self {
+        Self::with_policy(inner, num, per, Block)
+    }
+}
+
+impl<H, P> RateLimit<H, P> {
+    pub fn with_policy(inner: H, num: u32, per: Duration, policy: P) -> Self {
+        assert!(num > 0, "Num must be greater than 0.");
+        assert!(!per.is_zero(), "Per must be greater than 0.");
+
+        Self {
+            inner,
+            bucket: Bucket::new(num, per / num),
+            policy,
+        }
+    }
+
+    /// Sets the maximum number of calls let through at once after being idle.
+    pub fn burst(mut self, burst: u32) -> Self {
+        assert!(burst > 0, "Burst must be greater than 0.");
+
+        self.bucket.capacity = burst;
+        self.bucket.tokens = self.bucket.tokens.min(burst);
+        self
+    }
+
+    pub fn get_ref(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut H {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+}
+
+impl<H, Arg> Handler<Arg> for RateLimit<H, Block>
+where
+    H: Handler<Arg>,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        while let Err(wait) = self.bucket.try_acquire() {
+            thread::sleep(wait);
+        }
+        self.inner.handle(arg, callback);
+    }
+}
+
+impl<H, F, Arg> Handler<Arg> for RateLimit<H, Shed<F>>
+where
+    H: Handler<Arg>,
+    F: Fn() -> H::Ret,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        match self.bucket.try_acquire() {
+            Ok(()) => self.inner.handle(arg, callback),
+            Err(_) => callback.call((self.policy.0)()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: u32,
+    tokens: u32,
+    interval: Duration,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(capacity: u32, interval: Duration) -> Self {
+        Self {
+            capacity,
+            tokens: capacity,
+            // Rounded down intervals are kept positive.
+            interval: interval.max(Duration::from_nanos(1)),
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes a token, or returns how long until the next one is available.
+    fn try_acquire(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        self.refill(now);
+
+        if self.tokens == 0 {
+            return Err((self.last_refill + self.interval).saturating_duration_since(now));
+        }
+        self.tokens -= 1;
+        Ok(())
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let n = elapsed.as_nanos() / self.interval.as_nanos();
+        if n == 0 {
+            return;
+        }
+
+        let n = u32::try_from(n).unwrap_or(u32::MAX);
+        self.tokens = self.tokens.saturating_add(n).min(self.capacity);
+        if self.tokens == self.capacity {
+            self.last_refill = now;
+        } else {
+            self.last_refill += self.interval * n;
+        }
+    }
+}
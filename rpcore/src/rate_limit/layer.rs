@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use super::{Block, RateLimit, Shed};
+use crate::layer::Layer;
+
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer<P = Block> {
+    num: u32,
+    per: Duration,
+    burst: Option<u32>,
+    policy: P,
+}
+
+impl RateLimitLayer {
+    /// Allows `num` calls per `per`, and blocks when the limit is reached.
+    pub const fn new(num: u32, per: Duration) -> Self {
+        Self {
+            num,
+            per,
+            burst: None,
+            policy: Block,
+        }
+    }
+}
+
+impl<P> RateLimitLayer<P> {
+    /// Sheds calls when the limit is reached, calling back with `overloaded()`
+    /// instead of blocking.
+    pub fn shed<F>(self, overloaded: F) -> RateLimitLayer<Shed<F>> {
+        RateLimitLayer {
+            num: self.num,
+            per: self.per,
+            burst: self.burst,
+            policy: Shed(overloaded),
+        }
+    }
+
+    /// Sets the maximum number of calls let through at once after being idle,
+    /// `num` by default.
+    pub const fn burst(mut self, burst: u32) -> Self {
+        self.burst = Some(burst);
+        self
+    }
+}
+
+impl<H, P: Clone> Layer<H> for RateLimitLayer<P> {
+    type Handler = RateLimit<H, P>;
+
+    fn layer(&self, inner: H) -> Self::Handler {
+        let handler = RateLimit::with_policy(inner, self.num, self.per, self.policy.clone());
+        match self.burst {
+            Some(burst) => handler.burst(burst),
+            None => handler,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub use crate::policy::{Block, Shed};
+
+mod handler;
+pub use handler::RateLimit;
+
+mod layer;
+pub use layer::RateLimitLayer;
@@ -0,0 +1,174 @@
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::timer::Timer;
+use crate::{callback_fn, Callback, Handler};
+
+/// The delay before each retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    Fixed(Duration),
+    /// Doubles the delay after every retry, starting at `initial` and capped at
+    /// `max`.
+    Exponential {
+        initial: Duration,
+        max: Duration,
+    },
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff::Exponential {
+            initial: Duration::from_millis(10),
+            max: Duration::from_secs(1),
+        }
+    }
+}
+
+impl Backoff {
+    /// Returns the delay before the `retry`-th retry, counting from 0.
+    pub fn delay(&self, retry: u32) -> Duration {
+        match *self {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => initial
+                .checked_mul(1 << retry.min(31))
+                .map_or(max, |delay| delay.min(max)),
+        }
+    }
+}
+
+type Job<H> = Box<dyn FnOnce(&mut H) + Send>;
+
+/// Re-invokes the inner handler with a clone of the argument while
+/// `should_retry` holds for its return value, up to `max_attempts` attempts in
+/// total.
+///
+/// The callback is called once, with the return value of the last attempt.
+/// First attempts are handled by the inner handler itself. Retries are handled
+/// after their [`Backoff`] delay by a clone of it on a dedicated thread, so the
+/// inner handler and the arguments must be `Send`.
+pub struct Retry<H, P> {
+    inner: H,
+    shared: Arc<Shared<H, P>>,
+}
+
+struct Shared<H, P> {
+    should_retry: P,
+    max_attempts: u32,
+    backoff: Backoff,
+    retries: mpsc::Sender<Job<H>>,
+}
+
+impl<H, P> Retry<H, P>
+where
+    H: Clone + Send + 'static,
+{
+    /// Creates a new `Retry`, which spawns a thread that handles the retries.
+    pub fn new(inner: H, should_retry: P, max_attempts: u32, backoff: Backoff) -> Self {
+        assert!(max_attempts > 0, "Max attempts must be greater than 0.");
+
+        let (retries, rx) = mpsc::channel::<Job<H>>();
+        let mut cloned = inner.clone();
+        thread::Builder::new()
+            .name("rpcore-retry".to_owned())
+            .spawn(move || {
+                // Exits once the `Retry` and all its pending calls are dropped.
+                for job in rx {
+                    // A panicking retry drops its callback, but must not bring
+                    // down the thread.
+                    let _ = panic::catch_unwind(AssertUnwindSafe(|| job(&mut cloned)));
+                }
+            })
+            .expect("Failed to spawn retry thread.");
+
+        Self {
+            inner,
+            shared: Arc::new(Shared {
+                should_retry,
+                max_attempts,
+                backoff,
+                retries,
+            }),
+        }
+    }
+}
+
+impl<H, P> Retry<H, P> {
+    pub fn get_ref(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut H {
+        &mut self.inner
+    }
+
+    /// Returns the inner handler. The retries of pending calls are still
+    /// handled by its clone.
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+}
+
+impl<H, P, Arg> Handler<Arg> for Retry<H, P>
+where
+    H: Handler<Arg> + Send + 'static,
+    H::Ret: 'static,
+    P: Fn(&H::Ret) -> bool + Send + Sync + 'static,
+    Arg: Clone + Send + 'static,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        attempt(&mut self.inner, &self.shared, arg, callback, 0);
+    }
+}
+
+fn attempt<H, P, Arg, Cb>(
+    inner: &mut H,
+    shared: &Arc<Shared<H, P>>,
+    arg: Arg,
+    callback: Cb,
+    retry: u32,
+) where
+    H: Handler<Arg> + Send + 'static,
+    H::Ret: 'static,
+    P: Fn(&H::Ret) -> bool + Send + Sync + 'static,
+    Arg: Clone + Send + 'static,
+    Cb: Callback<Ret = H::Ret>,
+{
+    let retry_arg = arg.clone();
+    let cloned = Arc::clone(shared);
+    let callback = callback_fn(move |ret| {
+        if retry + 1 >= cloned.max_attempts || !(cloned.should_retry)(&ret) {
+            callback.call(ret);
+            return;
+        }
+
+        // Never retries in place, the inner handler may still be running if
+        // it called back synchronously. The timer only hands the retry over
+        // to the retry thread, so that a slow handler does not hold it up.
+        let deadline = Instant::now() + cloned.backoff.delay(retry);
+        let shared = Arc::clone(&cloned);
+        Timer::get().schedule(deadline, move || {
+            let retries = shared.retries.clone();
+            let _ = retries.send(Box::new(move |inner: &mut H| {
+                attempt(inner, &shared, retry_arg, callback, retry + 1);
+            }));
+        });
+    });
+
+    inner.handle(arg, callback);
+}
+
+impl<H: fmt::Debug, P> fmt::Debug for Retry<H, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Retry")
+            .field("inner", &self.inner)
+            .field("max_attempts", &self.shared.max_attempts)
+            .field("backoff", &self.shared.backoff)
+            .finish_non_exhaustive()
+    }
+}
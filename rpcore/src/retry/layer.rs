@@ -0,0 +1,49 @@
+use super::{Backoff, Retry};
+use crate::layer::Layer;
+
+#[derive(Debug, Clone)]
+pub struct RetryLayer<P> {
+    should_retry: P,
+    max_attempts: u32,
+    backoff: Backoff,
+}
+
+impl<P> RetryLayer<P> {
+    /// Retries while `should_retry` holds for the return value, up to 3
+    /// attempts in total with the default [`Backoff`].
+    pub fn new(should_retry: P) -> Self {
+        Self {
+            should_retry,
+            max_attempts: 3,
+            backoff: Backoff::default(),
+        }
+    }
+
+    /// Sets the maximum number of attempts, including the first one.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+}
+
+impl<H, P> Layer<H> for RetryLayer<P>
+where
+    H: Clone + Send + 'static,
+    P: Clone,
+{
+    type Handler = Retry<H, P>;
+
+    fn layer(&self, inner: H) -> Self::Handler {
+        Retry::new(
+            inner,
+            self.should_retry.clone(),
+            self.max_attempts,
+            self.backoff,
+        )
+    }
+}
@@ -0,0 +1,5 @@
+mod handler;
+pub use handler::{Backoff, Retry};
+
+mod layer;
+pub use layer::RetryLayer;
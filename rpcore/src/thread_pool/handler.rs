@@ -0,0 +1,177 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+
+use crate::policy::{Block, Shed};
+use crate::{Callback, Handler};
+
+type Job<H> = Box<dyn FnOnce(&mut H) + Send>;
+
+/// Handles calls on a fixed pool of worker threads, so that a slow handler
+/// does not hold up the serve thread.
+///
+/// Calls are queued up to the queue size, then picked up by the next idle
+/// worker, which calls back from its own thread. Each worker handles calls
+/// with its own clone of the inner handler, so state that must be seen by all
+/// of them should be behind an `Arc`.
+///
+/// What happens to calls when the queue is full depends on the policy `P`,
+/// which is one of [`Block`] and [`Shed`].
+///
+/// Once the pool is dropped, the workers exit after handling the queued calls.
+pub struct ThreadPool<H, P = Block> {
+    shared: Arc<Shared<H>>,
+    policy: P,
+}
+
+struct Shared<H> {
+    workers: usize,
+    queue_size: usize,
+    state: Mutex<State<H>>,
+    /// Notified when a job is pushed, or the pool is dropped.
+    pushed: Condvar,
+    /// Notified when a job is popped.
+    popped: Condvar,
+}
+
+struct State<H> {
+    jobs: VecDeque<Job<H>>,
+    dropped: bool,
+}
+
+impl<H> ThreadPool<H>
+where
+    H: Clone + Send + 'static,
+{
+    pub fn new(inner: H, workers: usize, queue_size: usize) -> Self {
+        Self::with_policy(inner, workers, queue_size, Block)
+    }
+}
+
+impl<H, P> ThreadPool<H, P>
+where
+    H: Clone + Send + 'static,
+{
+    pub fn with_policy(inner: H, workers: usize, queue_size: usize, policy: P) -> Self {
+        assert!(workers > 0, "Workers must be greater than 0.");
+        assert!(queue_size > 0, "Queue size must be greater than 0.");
+
+        let shared = Arc::new(Shared {
+            workers,
+            queue_size,
+            state: Mutex::new(State {
+                jobs: VecDeque::with_capacity(queue_size),
+                dropped: false,
+            }),
+            pushed: Condvar::new(),
+            popped: Condvar::new(),
+        });
+
+        for i in 0..workers {
+            let cloned = Arc::clone(&shared);
+            let inner = inner.clone();
+            thread::Builder::new()
+                .name(format!("rpcore-worker-{i}"))
+                .spawn(move || work(&cloned, inner))
+                .expect("Failed to spawn worker thread.");
+        }
+
+        Self { shared, policy }
+    }
+}
+
+impl<H, P> ThreadPool<H, P> {
+    /// Returns the number of calls waiting for a worker.
+    pub fn queued_count(&self) -> usize {
+        self.shared.state().jobs.len()
+    }
+}
+
+impl<H> Shared<H> {
+    fn state(&self) -> MutexGuard<'_, State<H>> {
+        self.state.lock().unwrap()
+    }
+}
+
+fn work<H>(shared: &Shared<H>, mut inner: H) {
+    loop {
+        let job = {
+            let mut state = shared.state();
+            loop {
+                if let Some(job) = state.jobs.pop_front() {
+                    break job;
+                }
+                if state.dropped {
+                    return;
+                }
+                state = shared.pushed.wait(state).unwrap();
+            }
+        };
+        shared.popped.notify_one();
+
+        // A panicking call drops its callback, but must not bring down the
+        // worker.
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| job(&mut inner)));
+    }
+}
+
+impl<H, P> Drop for ThreadPool<H, P> {
+    fn drop(&mut self) {
+        self.shared.state().dropped = true;
+        self.shared.pushed.notify_all();
+    }
+}
+
+impl<H, P: fmt::Debug> fmt::Debug for ThreadPool<H, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadPool")
+            .field("workers", &self.shared.workers)
+            .field("queue_size", &self.shared.queue_size)
+            .field("policy", &self.policy)
+            .field("queued_count", &self.queued_count())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<H, Arg> Handler<Arg> for ThreadPool<H, Block>
+where
+    H: Handler<Arg>,
+    Arg: Send + 'static,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        let mut state = self.shared.state();
+        while state.jobs.len() >= self.shared.queue_size {
+            state = self.shared.popped.wait(state).unwrap();
+        }
+        state
+            .jobs
+            .push_back(Box::new(move |inner: &mut H| inner.handle(arg, callback)));
+        self.shared.pushed.notify_one();
+    }
+}
+
+impl<H, F, Arg> Handler<Arg> for ThreadPool<H, Shed<F>>
+where
+    H: Handler<Arg>,
+    Arg: Send + 'static,
+    F: Fn() -> H::Ret,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        let mut state = self.shared.state();
+        if state.jobs.len() >= self.shared.queue_size {
+            drop(state);
+            callback.call((self.policy.0)());
+            return;
+        }
+        state
+            .jobs
+            .push_back(Box::new(move |inner: &mut H| inner.handle(arg, callback)));
+        self.shared.pushed.notify_one();
+    }
+}
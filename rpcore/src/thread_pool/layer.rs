@@ -0,0 +1,51 @@
+use super::{Block, Shed, ThreadPool};
+use crate::layer::Layer;
+
+#[derive(Debug, Clone)]
+pub struct ThreadPoolLayer<P = Block> {
+    workers: usize,
+    queue_size: usize,
+    policy: P,
+}
+
+impl ThreadPoolLayer {
+    /// Handles calls on `workers` threads, queueing up to `workers` calls and
+    /// blocking when the queue is full.
+    pub const fn new(workers: usize) -> Self {
+        Self {
+            workers,
+            queue_size: workers,
+            policy: Block,
+        }
+    }
+}
+
+impl<P> ThreadPoolLayer<P> {
+    /// Sets the maximum number of calls waiting for a worker.
+    pub const fn queue_size(mut self, queue_size: usize) -> Self {
+        self.queue_size = queue_size;
+        self
+    }
+
+    /// Sheds calls when the queue is full, calling back with `overloaded()`
+    /// instead of blocking.
+    pub fn shed<F>(self, overloaded: F) -> ThreadPoolLayer<Shed<F>> {
+        ThreadPoolLayer {
+            workers: self.workers,
+            queue_size: self.queue_size,
+            policy: Shed(overloaded),
+        }
+    }
+}
+
+impl<H, P> Layer<H> for ThreadPoolLayer<P>
+where
+    H: Clone + Send + 'static,
+    P: Clone,
+{
+    type Handler = ThreadPool<H, P>;
+
+    fn layer(&self, inner: H) -> Self::Handler {
+        ThreadPool::with_policy(inner, self.workers, self.queue_size, self.policy.clone())
+    }
+}
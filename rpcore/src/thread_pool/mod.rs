@@ -0,0 +1,7 @@
+pub use crate::policy::{Block, Shed};
+
+mod handler;
+pub use handler::ThreadPool;
+
+mod layer;
+pub use layer::ThreadPoolLayer;
@@ -0,0 +1,128 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::timer::Timer;
+use crate::{callback_fn, Callback, Handler};
+
+/// Produces the value that a [`Timeout`] calls back with when the inner
+/// handler does not call back in time.
+///
+/// It is implemented by closures returning the value, and by [`ElapsedErr`]
+/// for `Result` rets.
+pub trait OnTimeout<Ret> {
+    fn on_timeout(&self) -> Ret;
+}
+
+impl<F, Ret> OnTimeout<Ret> for F
+where
+    F: Fn() -> Ret,
+{
+    fn on_timeout(&self) -> Ret {
+        self()
+    }
+}
+
+/// Calls back with `Err(Elapsed.into())` on timeout.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ElapsedErr;
+
+impl<T, E> OnTimeout<Result<T, E>> for ElapsedErr
+where
+    E: From<Elapsed>,
+{
+    fn on_timeout(&self) -> Result<T, E> {
+        Err(Elapsed(()).into())
+    }
+}
+
+/// The error of a call that timed out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed(());
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("call timed out")
+    }
+}
+
+impl std::error::Error for Elapsed {}
+
+/// Calls back on behalf of the inner handler if it does not call back within
+/// `timeout`, and drops its late completion.
+///
+/// The timeouts fire on a timer thread shared by all `Timeout`s, and are
+/// cancelled once the inner handler calls back.
+pub struct Timeout<H, T> {
+    inner: H,
+    timeout: Duration,
+    on_timeout: Arc<T>,
+}
+
+impl<H, T> Timeout<H, T> {
+    pub fn new(inner: H, timeout: Duration, on_timeout: T) -> Self {
+        Self {
+            inner,
+            timeout,
+            on_timeout: Arc::new(on_timeout),
+        }
+    }
+
+    pub fn get_ref(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut H {
+        &mut self.inner
+    }
+
+    /// Returns the inner handler. The timeouts of pending calls still fire.
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+}
+
+impl<H, T, Arg> Handler<Arg> for Timeout<H, T>
+where
+    H: Handler<Arg>,
+    H::Ret: 'static,
+    T: OnTimeout<H::Ret> + Send + Sync + 'static,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        // Whichever of the inner handler and the timer takes the callback
+        // first calls it.
+        let slot = Arc::new(Mutex::new(Some(callback)));
+
+        let cloned = Arc::clone(&slot);
+        let on_timeout = Arc::clone(&self.on_timeout);
+        let timer = Timer::get();
+        let key = timer.schedule(Instant::now() + self.timeout, move || {
+            let callback = cloned.lock().unwrap().take();
+            if let Some(callback) = callback {
+                callback.call(on_timeout.on_timeout());
+            }
+        });
+
+        self.inner.handle(
+            arg,
+            callback_fn(move |ret| {
+                let callback = slot.lock().unwrap().take();
+                if let Some(callback) = callback {
+                    timer.cancel(key);
+                    callback.call(ret);
+                }
+            }),
+        );
+    }
+}
+
+impl<H: fmt::Debug, T> fmt::Debug for Timeout<H, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timeout")
+            .field("inner", &self.inner)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
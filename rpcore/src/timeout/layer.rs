@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use super::{ElapsedErr, Timeout};
+use crate::layer::Layer;
+
+#[derive(Debug, Clone)]
+pub struct TimeoutLayer<T> {
+    timeout: Duration,
+    on_timeout: T,
+}
+
+impl<T> TimeoutLayer<T> {
+    /// Calls back with `on_timeout.on_timeout()` when the handler does not call
+    /// back within `timeout`.
+    pub const fn new(timeout: Duration, on_timeout: T) -> Self {
+        Self {
+            timeout,
+            on_timeout,
+        }
+    }
+}
+
+impl TimeoutLayer<ElapsedErr> {
+    /// Calls back with an [`Elapsed`](super::Elapsed) error when the handler
+    /// does not call back within `timeout`, for handlers that return a
+    /// `Result`.
+    pub const fn elapsed(timeout: Duration) -> Self {
+        Self::new(timeout, ElapsedErr)
+    }
+}
+
+impl<H, T: Clone> Layer<H> for TimeoutLayer<T> {
+    type Handler = Timeout<H, T>;
+
+    fn layer(&self, inner: H) -> Self::Handler {
+        Timeout::new(inner, self.timeout, self.on_timeout.clone())
+    }
+}
@@ -0,0 +1,5 @@
+mod handler;
+pub use handler::{Elapsed, ElapsedErr, OnTimeout, Timeout};
+
+mod layer;
+pub use layer::TimeoutLayer;
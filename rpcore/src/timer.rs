@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::Instant;
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Identifies a scheduled task, to cancel it.
+///
+/// Keys are ordered by deadline, then by scheduling order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Key {
+    deadline: Instant,
+    seq: u64,
+}
+
+#[derive(Default)]
+struct State {
+    tasks: BTreeMap<Key, Task>,
+    next_seq: u64,
+}
+
+/// Runs tasks at their deadlines on a thread shared by all the layers.
+///
+/// Tasks run one after another, so they should only do little work, such as
+/// calling back or handing the work over to another thread.
+pub(crate) struct Timer {
+    state: Mutex<State>,
+    cvar: Condvar,
+}
+
+impl Timer {
+    /// Returns the timer, spawning its thread on first use.
+    pub(crate) fn get() -> &'static Timer {
+        static TIMER: OnceLock<Timer> = OnceLock::new();
+        TIMER.get_or_init(|| {
+            thread::Builder::new()
+                .name("rpcore-timer".to_owned())
+                // Waits for the initialization to finish.
+                .spawn(|| run(Timer::get()))
+                .expect("Failed to spawn timer thread.");
+
+            Timer {
+                state: Mutex::default(),
+                cvar: Condvar::new(),
+            }
+        })
+    }
+
+    pub(crate) fn schedule(&self, deadline: Instant, task: impl FnOnce() + Send + 'static) -> Key {
+        let mut state = self.state();
+        let key = Key {
+            deadline,
+            seq: state.next_seq,
+        };
+        state.next_seq += 1;
+        state.tasks.insert(key, Box::new(task));
+        self.cvar.notify_one();
+        key
+    }
+
+    /// Drops the task scheduled under `key`, unless it has already run.
+    pub(crate) fn cancel(&self, key: Key) {
+        let task = self.state().tasks.remove(&key);
+        // The task may call back when dropped, which must not happen under
+        // the lock.
+        drop(task);
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn run(timer: &Timer) {
+    let mut state = timer.state();
+    loop {
+        let now = Instant::now();
+        match state.tasks.first_key_value() {
+            Some((key, _)) if key.deadline <= now => {
+                let (_, task) = state.tasks.pop_first().unwrap();
+                // Tasks call back into user code, which must not run under
+                // the lock, nor bring down the timer if it panics.
+                drop(state);
+                let _ = panic::catch_unwind(AssertUnwindSafe(task));
+                state = timer.state();
+            }
+            Some((key, _)) => {
+                let dur = key.deadline - now;
+                state = timer.cvar.wait_timeout(state, dur).unwrap().0;
+            }
+            None => state = timer.cvar.wait(state).unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn runs_tasks_at_their_deadlines() {
+        let (tx, rx) = mpsc::channel();
+        let start = Instant::now();
+        for millis in [30, 10, 20] {
+            let tx = tx.clone();
+            Timer::get().schedule(start + Duration::from_millis(millis), move || {
+                tx.send(millis).unwrap();
+            });
+        }
+
+        let fired: Vec<_> = rx.iter().take(3).collect();
+        assert_eq!(fired, [10, 20, 30]);
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn cancel_drops_the_task() {
+        let (tx, rx) = mpsc::channel::<()>();
+        let timer = Timer::get();
+        let key = timer.schedule(Instant::now() + Duration::from_secs(60), move || {
+            drop(tx);
+        });
+
+        timer.cancel(key);
+        assert!(!timer.state().tasks.contains_key(&key));
+        // The sender was dropped with the task.
+        assert!(rx.recv().is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use std::any::type_name;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Instant;
+
+use tracing::field::{debug, Empty};
+use tracing::{Level, Span};
+
+use crate::server::{GetToken, Token};
+use crate::{callback_fn, Callback, Handler};
+
+/// Picks the token recorded on the span of a call, see [`NoToken`] and
+/// [`ArgToken`].
+pub trait SpanToken<Arg> {
+    fn span_token(arg: &Arg) -> Option<Token>;
+}
+
+/// Records no token.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoToken;
+
+impl<Arg> SpanToken<Arg> for NoToken {
+    fn span_token(_: &Arg) -> Option<Token> {
+        None
+    }
+}
+
+/// Records the token of arguments implementing [`GetToken`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArgToken;
+
+impl<Arg: GetToken> SpanToken<Arg> for ArgToken {
+    fn span_token(arg: &Arg) -> Option<Token> {
+        Some(arg.token())
+    }
+}
+
+/// Records a value of type `T` on a span, or not, see [`Recorded`] and
+/// [`Redacted`].
+pub trait SpanValue<T> {
+    fn record(span: &Span, field: &str, value: &T);
+}
+
+/// Records values with their `Debug` impl.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Recorded;
+
+impl<T: fmt::Debug> SpanValue<T> for Recorded {
+    fn record(span: &Span, field: &str, value: &T) {
+        span.record(field, debug(value));
+    }
+}
+
+/// Leaves values out of the span, so they need not implement `Debug`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Redacted;
+
+impl<T> SpanValue<T> for Redacted {
+    fn record(_: &Span, _: &str, _: &T) {}
+}
+
+/// Opens a `tracing` span named `rpcore.handle` for every call, which closes
+/// when the callback is called.
+///
+/// The span carries the `handler` name, the `token` picked by `K`, the
+/// `elapsed` time in microseconds, and the `arg` and `ret` as recorded by `A`
+/// and `R`.
+pub struct Trace<H, K = NoToken, A = Recorded, R = Recorded> {
+    inner: H,
+    handler_name: Arc<str>,
+    level: Level,
+    _token: PhantomData<fn() -> K>,
+    _values: PhantomData<fn() -> (A, R)>,
+}
+
+impl<H, K, A, R> Trace<H, K, A, R> {
+    pub fn new(inner: H, handler_name: Option<String>, level: Level) -> Self {
+        let handler_name = handler_name.unwrap_or(type_name::<H>().to_owned());
+        Self {
+            inner,
+            handler_name: handler_name.into(),
+            level,
+            _token: PhantomData,
+            _values: PhantomData,
+        }
+    }
+
+    pub fn get_ref(&self) -> &H {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut H {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+
+    fn span(&self) -> Span {
+        macro_rules! span {
+            ($level:expr) => {
+                tracing::span!(
+                    $level,
+                    "rpcore.handle",
+                    handler = &*self.handler_name,
+                    token = Empty,
+                    arg = Empty,
+                    ret = Empty,
+                    elapsed = Empty,
+                )
+            };
+        }
+
+        match self.level {
+            Level::ERROR => span!(Level::ERROR),
+            Level::WARN => span!(Level::WARN),
+            Level::INFO => span!(Level::INFO),
+            Level::DEBUG => span!(Level::DEBUG),
+            Level::TRACE => span!(Level::TRACE),
+        }
+    }
+}
+
+impl<H, K, A, R, Arg> Handler<Arg> for Trace<H, K, A, R>
+where
+    H: Handler<Arg>,
+    H::Ret: 'static,
+    K: SpanToken<Arg>,
+    A: SpanValue<Arg>,
+    R: SpanValue<H::Ret>,
+{
+    type Ret = H::Ret;
+
+    fn handle(&mut self, arg: Arg, callback: impl Callback<Ret = Self::Ret>) {
+        let span = self.span();
+        if !span.is_disabled() {
+            if let Some(token) = K::span_token(&arg) {
+                span.record("token", usize::from(token));
+            }
+            A::record(&span, "arg", &arg);
+        }
+
+        let begin_at = Instant::now();
+        let cloned = span.clone();
+        let _entered = span.enter();
+        self.inner.handle(
+            arg,
+            callback_fn(move |ret| {
+                let span = cloned;
+                if !span.is_disabled() {
+                    span.record("elapsed", begin_at.elapsed().as_micros() as u64);
+                    R::record(&span, "ret", &ret);
+                }
+                drop(span);
+                callback.call(ret);
+            }),
+        );
+    }
+}
+
+impl<H: fmt::Debug, K, A, R> fmt::Debug for Trace<H, K, A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Trace")
+            .field("inner", &self.inner)
+            .field("handler_name", &self.handler_name)
+            .field("level", &self.level)
+            .finish_non_exhaustive()
+    }
+}
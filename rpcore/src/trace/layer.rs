@@ -0,0 +1,90 @@
+use std::marker::PhantomData;
+
+use tracing::Level;
+
+use super::{ArgToken, NoToken, Recorded, Redacted, Trace};
+use crate::layer::Layer;
+
+#[derive(Debug)]
+pub struct TraceLayer<K = NoToken, A = Recorded, R = Recorded> {
+    handler_name: Option<String>,
+    level: Level,
+    _token: PhantomData<fn() -> K>,
+    _values: PhantomData<fn() -> (A, R)>,
+}
+
+impl Default for TraceLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceLayer {
+    /// Opens spans at the `INFO` level that record the `arg` and `ret`.
+    pub const fn new() -> Self {
+        Self {
+            handler_name: None,
+            level: Level::INFO,
+            _token: PhantomData,
+            _values: PhantomData,
+        }
+    }
+}
+
+impl<K, A, R> TraceLayer<K, A, R> {
+    /// Sets the name recorded as `handler`, the type name of the handler by
+    /// default.
+    pub fn handler_name(mut self, handler_name: impl Into<String>) -> Self {
+        self.handler_name = Some(handler_name.into());
+        self
+    }
+
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Leaves the `arg` out of the span, so it need not implement `Debug`.
+    pub fn redact_arg(self) -> TraceLayer<K, Redacted, R> {
+        self.retype()
+    }
+
+    /// Leaves the `ret` out of the span, so it need not implement `Debug`.
+    pub fn redact_ret(self) -> TraceLayer<K, A, Redacted> {
+        self.retype()
+    }
+
+    /// Records the token of arguments implementing
+    /// [`GetToken`](crate::server::GetToken).
+    pub fn with_token(self) -> TraceLayer<ArgToken, A, R> {
+        self.retype()
+    }
+
+    fn retype<K2, A2, R2>(self) -> TraceLayer<K2, A2, R2> {
+        TraceLayer {
+            handler_name: self.handler_name,
+            level: self.level,
+            _token: PhantomData,
+            _values: PhantomData,
+        }
+    }
+}
+
+impl<K, A, R> Clone for TraceLayer<K, A, R> {
+    fn clone(&self) -> Self {
+        Self {
+            handler_name: self.handler_name.clone(),
+            level: self.level,
+            _token: PhantomData,
+            _values: PhantomData,
+        }
+    }
+}
+
+impl<H, K, A, R> Layer<H> for TraceLayer<K, A, R> {
+    type Handler = Trace<H, K, A, R>;
+
+    fn layer(&self, inner: H) -> Self::Handler {
+        Trace::new(inner, self.handler_name.clone(), self.level)
+    }
+}
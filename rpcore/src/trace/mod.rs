@@ -0,0 +1,5 @@
+mod handler;
+pub use handler::{ArgToken, NoToken, Recorded, Redacted, SpanToken, SpanValue, Trace};
+
+mod layer;
+pub use layer::TraceLayer;